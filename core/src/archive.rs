@@ -0,0 +1,92 @@
+use glob::glob;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use zip::ZipArchive;
+
+use crate::debug;
+
+const EXTENSIONS: [&str; 2] = ["zip", "grimmod"];
+
+/// A mod packaged as a single `.zip` or `.grimmod` archive
+///
+/// The archive can either hold the mod's `info.json` and `assets` folder at its root,
+/// or inside a single top-level folder (as most tools do when zipping a folder)
+pub struct ModArchive {
+    pub path: PathBuf,
+    pub name: String,
+    archive: Mutex<ZipArchive<File>>,
+    info: Option<String>,
+    assets: Vec<(String, String)>,
+}
+
+impl ModArchive {
+    pub fn open<P: AsRef<Path>>(path: P) -> Option<ModArchive> {
+        let path = path.as_ref();
+        let archive = ZipArchive::new(File::open(path).ok()?).ok()?;
+        let name = path.file_stem()?.to_str()?.to_string();
+
+        let mut info = None;
+        let mut assets = Vec::new();
+        for entry_name in archive.file_names() {
+            let components: Vec<_> = entry_name.split('/').collect();
+            match components.as_slice() {
+                ["info.json"] | [_, "info.json"] => info = Some(entry_name.to_string()),
+                ["assets", _, filename] | [_, "assets", _, filename] if !filename.is_empty() => {
+                    assets.push((filename.to_lowercase(), entry_name.to_string()));
+                }
+                _ => {}
+            }
+        }
+
+        Some(ModArchive {
+            path: path.to_owned(),
+            name,
+            archive: Mutex::new(archive),
+            info,
+            assets,
+        })
+    }
+
+    /// Opens every archive in the mods folder, logging the ones that can't be read
+    pub fn open_all() -> Vec<ModArchive> {
+        EXTENSIONS
+            .iter()
+            .filter_map(|extension| glob(&format!("./Mods/*.{}", extension)).ok())
+            .flatten()
+            .filter_map(Result::ok)
+            .filter_map(|path| {
+                let archive = ModArchive::open(&path);
+                if archive.is_none() {
+                    debug::error(format!("Could not open mod archive {}", path.display()));
+                }
+                archive
+            })
+            .collect()
+    }
+
+    /// Reads a whole entry out of the archive
+    pub fn read(&self, entry_name: &str) -> io::Result<Vec<u8>> {
+        let mut archive = self.archive.lock().unwrap();
+        let mut entry = archive.by_name(entry_name)?;
+        // the size in the archive's metadata can't be trusted to allocate up front
+        let mut buffer = Vec::new();
+        entry.read_to_end(&mut buffer)?;
+        Ok(buffer)
+    }
+
+    /// Reads the mod's `info.json`, if it has one
+    pub fn read_info(&self) -> Option<String> {
+        let bytes = self.read(self.info.as_ref()?).ok()?;
+        String::from_utf8(bytes).ok()
+    }
+
+    /// The lowercase filename and entry name of every asset in the archive,
+    /// a filename appears once per variant folder it's in
+    pub fn assets(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.assets
+            .iter()
+            .map(|(filename, entry)| (filename.as_str(), entry.as_str()))
+    }
+}
@@ -0,0 +1,434 @@
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+
+const MAGIC: &[u8; 8] = b"BM  F\0\0\0";
+const HEADER_SIZE: usize = 128;
+const IMAGE_HEADER_SIZE: usize = 8;
+// far beyond the game's 640x480, but keeps a corrupt size from allocating gigabytes
+const MAX_DIMENSION: u32 = 4096;
+// color bitmaps mark transparent pixels with magenta rather than using the header's color
+const TRANSPARENT: u16 = 0xf81f;
+
+/// What the pixels of a bitmap hold
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BmFormat {
+    /// RGB565 colors, used by backgrounds and overlays (`.bm`)
+    Color,
+    /// 16-bit depth values, used to layer actors into backgrounds (`.zbm`)
+    Depth,
+}
+
+/// How the pixels of every image in a bitmap are stored
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BmCodec {
+    Raw,
+    /// An LZ77 variant with a 16-bit flag stream interleaved with the data
+    Compressed,
+}
+
+#[derive(Clone, Debug)]
+pub struct BmHeader {
+    pub codec: BmCodec,
+    pub format: BmFormat,
+    pub image_count: usize,
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// A single image of a bitmap, bitmaps with more than one are animated or swapped by scripts
+#[derive(Clone, Debug)]
+pub struct BmImage {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u16>,
+}
+
+/// A BM or ZBM bitmap, the format the game stores all its 2D images in
+///
+/// The layout is a 128 byte header (`BM  F\0\0\0`, codec, palette flag, image count,
+/// position, transparent color, format, bits per pixel, color masks and padding),
+/// followed by every image as its width, height and pixel data. The pixel data is
+/// compressed for codec 3. All values are little endian.
+#[derive(Clone, Debug)]
+pub struct Bm {
+    pub header: BmHeader,
+    pub images: Vec<BmImage>,
+}
+
+impl Bm {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Bm, BmError> {
+        let mut bytes = Vec::new();
+        BufReader::new(File::open(path)?).read_to_end(&mut bytes)?;
+        Bm::parse(&bytes)
+    }
+
+    /// Decodes a bitmap and every image in it
+    pub fn parse(bytes: &[u8]) -> Result<Bm, BmError> {
+        let header = Bm::parse_header(bytes)?;
+
+        let mut offset = HEADER_SIZE;
+        // every image takes at least its header, so a corrupt count can't allocate much
+        let max_images = (bytes.len() - HEADER_SIZE) / IMAGE_HEADER_SIZE;
+        if header.image_count > max_images {
+            return Err(BmError::Truncated);
+        }
+        let mut images = Vec::with_capacity(header.image_count);
+        for _ in 0..header.image_count {
+            let image_header = take(bytes, &mut offset, IMAGE_HEADER_SIZE)?;
+            let width = read_u32(image_header, 0);
+            let height = read_u32(image_header, 4);
+            if width > MAX_DIMENSION || height > MAX_DIMENSION {
+                return Err(BmError::TooLarge(width, height));
+            }
+
+            let size = (width as usize)
+                .checked_mul(height as usize)
+                .and_then(|pixels| pixels.checked_mul(2))
+                .ok_or(BmError::TooLarge(width, height))?;
+            let data = match header.codec {
+                BmCodec::Raw => take(bytes, &mut offset, size)?.to_vec(),
+                BmCodec::Compressed => {
+                    let compressed_size = read_u32(take(bytes, &mut offset, 4)?, 0);
+                    let compressed = take(bytes, &mut offset, compressed_size as usize)?;
+                    decompress(compressed, size)?
+                }
+            };
+
+            let pixels = data
+                .chunks_exact(2)
+                .map(|pixel| u16::from_le_bytes([pixel[0], pixel[1]]))
+                .collect();
+            images.push(BmImage {
+                width,
+                height,
+                pixels,
+            });
+        }
+
+        Ok(Bm { header, images })
+    }
+
+    /// Reads only the header, e.g. to find the size of a bitmap without decoding it
+    pub fn parse_header(bytes: &[u8]) -> Result<BmHeader, BmError> {
+        let header = bytes
+            .get(..HEADER_SIZE + IMAGE_HEADER_SIZE)
+            .ok_or(BmError::Truncated)?;
+        if &header[0..8] != MAGIC {
+            return Err(BmError::InvalidHeader);
+        }
+
+        let codec = match read_u32(header, 8) {
+            0 => BmCodec::Raw,
+            3 => BmCodec::Compressed,
+            codec => return Err(BmError::UnsupportedCodec(codec)),
+        };
+        let format = match read_u32(header, 32) {
+            1 => BmFormat::Color,
+            5 => BmFormat::Depth,
+            format => return Err(BmError::UnsupportedFormat(format)),
+        };
+        let bits_per_pixel = read_u32(header, 36);
+        if bits_per_pixel != 16 {
+            return Err(BmError::UnsupportedDepth(bits_per_pixel));
+        }
+
+        Ok(BmHeader {
+            codec,
+            format,
+            image_count: read_u32(header, 16) as usize,
+            x: read_u32(header, 20),
+            y: read_u32(header, 24),
+            // the first image's size, which the game uses for all of them
+            width: read_u32(header, HEADER_SIZE),
+            height: read_u32(header, HEADER_SIZE + 4),
+        })
+    }
+
+    /// Converts an image of a color bitmap to 8-bit RGBA, with transparent pixels cleared
+    pub fn rgba(&self, index: usize) -> Option<Vec<u8>> {
+        if self.header.format != BmFormat::Color {
+            return None;
+        }
+        let image = self.images.get(index)?;
+        Some(image.pixels.iter().flat_map(|&pixel| rgba(pixel)).collect())
+    }
+
+    /// The depth values of an image of a depth bitmap
+    pub fn depth(&self, index: usize) -> Option<&[u16]> {
+        if self.header.format != BmFormat::Depth {
+            return None;
+        }
+        self.images.get(index).map(|image| image.pixels.as_slice())
+    }
+}
+
+fn rgba(pixel: u16) -> [u8; 4] {
+    if pixel == TRANSPARENT {
+        return [0; 4];
+    }
+    let red = (pixel >> 11) as u8 & 0x1f;
+    let green = (pixel >> 5) as u8 & 0x3f;
+    let blue = pixel as u8 & 0x1f;
+    [
+        (red << 3) | (red >> 2),
+        (green << 2) | (green >> 4),
+        (blue << 3) | (blue >> 2),
+        0xff,
+    ]
+}
+
+/// Decompresses codec 3 image data
+///
+/// A stream of flag bits, read 16 at a time, says whether the next byte is a literal
+/// or a back-reference. Short back-references take a byte (offset up to 256 back,
+/// length 3 to 6), long ones two (offset up to 4096 back, length 4 to 18) plus a
+/// length byte if their length is 0, where a length byte of 0 ends the data.
+fn decompress(compressed: &[u8], size: usize) -> Result<Vec<u8>, BmError> {
+    let mut input = compressed.iter().copied();
+    let mut next = || input.next().ok_or(BmError::Truncated);
+    // the size is only a limit, corrupt data can end long before it
+    let mut result = Vec::with_capacity(size.min(compressed.len()));
+
+    let mut bits = u16::from_le_bytes([next()?, next()?]);
+    let mut bits_left = 16;
+    let mut next_bit = |next: &mut dyn FnMut() -> Result<u8, BmError>| {
+        let bit = bits & 1 == 1;
+        bits >>= 1;
+        bits_left -= 1;
+        if bits_left == 0 {
+            bits = u16::from_le_bytes([next()?, next()?]);
+            bits_left = 16;
+        }
+        Ok::<_, BmError>(bit)
+    };
+
+    loop {
+        if next_bit(&mut next)? {
+            result.push(next()?);
+        } else {
+            let (length, offset) = if !next_bit(&mut next)? {
+                let length = 2 * next_bit(&mut next)? as usize + next_bit(&mut next)? as usize + 3;
+                (length, 0x100 - next()? as usize)
+            } else {
+                let (low, high) = (next()? as usize, next()? as usize);
+                let offset = 0x1000 - (low | (high & 0xf0) << 4);
+                let length = match high & 0xf {
+                    0 => match next()? {
+                        0 => break,
+                        length => length as usize + 1,
+                    },
+                    length => length + 3,
+                };
+                (length, offset)
+            };
+
+            let start = result
+                .len()
+                .checked_sub(offset)
+                .ok_or(BmError::InvalidData)?;
+            for i in start..start + length {
+                let byte = result[i];
+                result.push(byte);
+            }
+        }
+
+        if result.len() > size {
+            return Err(BmError::InvalidData);
+        }
+    }
+
+    // like the game, data that ends early leaves the rest of the image blank
+    result.resize(size, 0);
+    Ok(result)
+}
+
+/// The next `len` bytes, moving the offset past them
+fn take<'a>(bytes: &'a [u8], offset: &mut usize, len: usize) -> Result<&'a [u8], BmError> {
+    let end = offset.checked_add(len).ok_or(BmError::Truncated)?;
+    let taken = bytes.get(*offset..end).ok_or(BmError::Truncated)?;
+    *offset = end;
+    Ok(taken)
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    let mut value = [0u8; 4];
+    value.copy_from_slice(&bytes[offset..offset + 4]);
+    u32::from_le_bytes(value)
+}
+
+pub enum BmError {
+    Io(io::Error),
+    InvalidHeader,
+    UnsupportedCodec(u32),
+    UnsupportedFormat(u32),
+    UnsupportedDepth(u32),
+    TooLarge(u32, u32),
+    Truncated,
+    InvalidData,
+}
+
+impl From<io::Error> for BmError {
+    fn from(err: io::Error) -> BmError {
+        BmError::Io(err)
+    }
+}
+
+impl std::fmt::Display for BmError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BmError::Io(err) => write!(f, "Could not read bitmap: {}", err),
+            BmError::InvalidHeader => write!(f, "Not a BM bitmap"),
+            BmError::UnsupportedCodec(codec) => write!(f, "Unsupported bitmap codec {}", codec),
+            BmError::UnsupportedFormat(format) => {
+                write!(f, "Unsupported bitmap format {}", format)
+            }
+            BmError::UnsupportedDepth(bits) => write!(f, "Unsupported {}-bit bitmap", bits),
+            BmError::TooLarge(width, height) => {
+                write!(f, "Bitmap is too large ({}x{})", width, height)
+            }
+            BmError::Truncated => write!(f, "Bitmap ends early"),
+            BmError::InvalidData => write!(f, "Bitmap data is corrupt"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(codec: u32, format: u32, image_count: u32) -> Vec<u8> {
+        let mut bytes = vec![0u8; HEADER_SIZE];
+        bytes[0..8].copy_from_slice(MAGIC);
+        for (offset, value) in [(8, codec), (16, image_count), (32, format), (36, 16)] {
+            bytes[offset..offset + 4].copy_from_slice(&u32::to_le_bytes(value));
+        }
+        bytes
+    }
+
+    fn image(bytes: &mut Vec<u8>, width: u32, height: u32, data: &[u8]) {
+        bytes.extend(width.to_le_bytes());
+        bytes.extend(height.to_le_bytes());
+        bytes.extend(data);
+    }
+
+    fn parse(bytes: &[u8]) -> Bm {
+        Bm::parse(bytes).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    fn compressed(data: &[u8]) -> Vec<u8> {
+        let mut bytes = (data.len() as u32).to_le_bytes().to_vec();
+        bytes.extend(data);
+        bytes
+    }
+
+    // two literals, a short back-reference repeating them for 6 bytes, then the end marker
+    const COMPRESSED: [u8; 8] = [0xb3, 0x00, 0x01, 0x02, 0xfe, 0x00, 0x00, 0x00];
+
+    #[test]
+    fn raw() {
+        let mut bytes = header(0, 1, 1);
+        image(&mut bytes, 2, 1, &[0x1f, 0xf8, 0xff, 0xff]);
+        let bm = parse(&bytes);
+
+        assert_eq!(bm.header.codec, BmCodec::Raw);
+        assert_eq!((bm.header.width, bm.header.height), (2, 1));
+        assert_eq!(bm.images[0].pixels, [TRANSPARENT, 0xffff]);
+        assert_eq!(bm.rgba(0).unwrap(), [0, 0, 0, 0, 0xff, 0xff, 0xff, 0xff]);
+        assert!(bm.depth(0).is_none());
+    }
+
+    #[test]
+    fn codec_3() {
+        let mut bytes = header(3, 1, 1);
+        image(&mut bytes, 2, 2, &compressed(&COMPRESSED));
+        let bm = parse(&bytes);
+
+        assert_eq!(bm.header.codec, BmCodec::Compressed);
+        assert_eq!(bm.images[0].pixels, [0x0201; 4]);
+    }
+
+    #[test]
+    fn codec_3_ending_early_is_blank() {
+        let mut bytes = header(3, 1, 1);
+        image(&mut bytes, 4, 2, &compressed(&COMPRESSED));
+        let bm = parse(&bytes);
+
+        assert_eq!(bm.images[0].pixels[..4], [0x0201; 4]);
+        assert_eq!(bm.images[0].pixels[4..], [0; 4]);
+    }
+
+    #[test]
+    fn multiple_images() {
+        let mut bytes = header(0, 1, 2);
+        image(&mut bytes, 1, 1, &[0x01, 0x00]);
+        image(&mut bytes, 1, 1, &[0x02, 0x00]);
+        let bm = parse(&bytes);
+
+        assert_eq!(bm.header.image_count, 2);
+        assert_eq!(bm.images[0].pixels, [1]);
+        assert_eq!(bm.images[1].pixels, [2]);
+        assert!(bm.rgba(2).is_none());
+    }
+
+    #[test]
+    fn zbm() {
+        let mut bytes = header(0, 5, 1);
+        image(&mut bytes, 2, 1, &[0x34, 0x12, 0xff, 0xff]);
+        let bm = parse(&bytes);
+
+        assert_eq!(bm.header.format, BmFormat::Depth);
+        assert_eq!(bm.depth(0).unwrap(), [0x1234, 0xffff]);
+        assert!(bm.rgba(0).is_none());
+    }
+
+    #[test]
+    fn unsupported_header() {
+        assert!(matches!(Bm::parse(&[0; 136]), Err(BmError::InvalidHeader)));
+        let mut bytes = header(2, 1, 1);
+        image(&mut bytes, 1, 1, &[0, 0]);
+        assert!(matches!(
+            Bm::parse(&bytes),
+            Err(BmError::UnsupportedCodec(2))
+        ));
+    }
+
+    #[test]
+    fn truncated() {
+        assert!(matches!(Bm::parse(MAGIC), Err(BmError::Truncated)));
+
+        // the compressed size itself is cut short
+        let mut bytes = header(3, 1, 1);
+        image(&mut bytes, 2, 2, &[0x08, 0x00]);
+        assert_eq!(bytes.len(), 138);
+        assert!(matches!(Bm::parse(&bytes), Err(BmError::Truncated)));
+
+        let mut bytes = header(3, 1, 1);
+        image(&mut bytes, 2, 2, &compressed(&COMPRESSED[..6]));
+        assert!(matches!(Bm::parse(&bytes), Err(BmError::Truncated)));
+
+        let mut bytes = header(0, 1, 1);
+        image(&mut bytes, 2, 2, &[0; 6]);
+        assert!(matches!(Bm::parse(&bytes), Err(BmError::Truncated)));
+    }
+
+    #[test]
+    fn oversized_header() {
+        let mut bytes = header(0, 1, 1);
+        image(&mut bytes, u32::MAX, u32::MAX, &[0; 8]);
+        assert!(matches!(
+            Bm::parse(&bytes),
+            Err(BmError::TooLarge(u32::MAX, u32::MAX))
+        ));
+
+        let mut bytes = header(3, 1, 1);
+        image(&mut bytes, 0x8000, 0x8000, &compressed(&COMPRESSED));
+        assert!(matches!(Bm::parse(&bytes), Err(BmError::TooLarge(..))));
+
+        let mut bytes = header(0, 1, u32::MAX);
+        image(&mut bytes, 1, 1, &[0, 0]);
+        assert!(matches!(Bm::parse(&bytes), Err(BmError::Truncated)));
+    }
+}
@@ -0,0 +1,934 @@
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, SystemTime};
+
+use crate::debug::{self, Level, Subsystem};
+
+const CONFIG_FILENAME: &str = "grimmod.toml";
+/// Every setting with its default and an explanation, written when there is no config yet
+pub const DEFAULT_CONFIG: &str = include_str!("grimmod.toml");
+
+pub static CONFIG: Lazy<RwLock<Config>> = Lazy::new(|| RwLock::new(Config::load()));
+static RELOAD_PENDING: AtomicBool = AtomicBool::new(false);
+
+// logging needs the config itself, so problems found while loading it are kept until `report`
+static DIAGNOSTICS: Mutex<Vec<Diagnostic>> = Mutex::new(Vec::new());
+
+/// A problem found in `grimmod.toml`
+pub enum Diagnostic {
+    Unreadable(String),
+    Syntax {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    WrongType {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    UnknownKey(String),
+    Created,
+    Upgraded(Vec<String>),
+    Unwritable(String),
+    Overridden(String),
+    BadOverride {
+        source: String,
+        message: String,
+    },
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Diagnostic::Unreadable(err) => {
+                write!(
+                    f,
+                    "Could not read {}, using defaults: {}",
+                    CONFIG_FILENAME, err
+                )
+            }
+            Diagnostic::Syntax {
+                line,
+                column,
+                message,
+            } => write!(
+                f,
+                "{}:{}:{} is not valid TOML, using defaults: {}",
+                CONFIG_FILENAME, line, column, message
+            ),
+            Diagnostic::WrongType {
+                line,
+                column,
+                message,
+            } => write!(
+                f,
+                "{}:{}:{} ignored, using its default: {}",
+                CONFIG_FILENAME, line, column, message
+            ),
+            Diagnostic::UnknownKey(key) => {
+                write!(f, "{} has an unknown setting {}", CONFIG_FILENAME, key)
+            }
+            Diagnostic::Created => write!(f, "Created {} with the defaults", CONFIG_FILENAME),
+            Diagnostic::Upgraded(keys) => write!(
+                f,
+                "Added new settings to {}: {}",
+                CONFIG_FILENAME,
+                keys.join(", ")
+            ),
+            Diagnostic::Unwritable(err) => {
+                write!(f, "Could not write {}: {}", CONFIG_FILENAME, err)
+            }
+            Diagnostic::Overridden(item) => write!(f, "Config override: {}", item),
+            Diagnostic::BadOverride { source, message } => {
+                write!(f, "Config override {} ignored: {}", source, message)
+            }
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct Config {
+    #[serde(default = "Mods::new")]
+    pub mods: Mods,
+    #[serde(default)]
+    pub load_order: Vec<String>,
+    #[serde(default = "VersionPolicy::new")]
+    pub version_policy: VersionPolicy,
+    #[serde(default = "default_variants")]
+    pub variants: Vec<String>,
+    #[serde(default = "Renderer::new")]
+    pub renderer: Renderer,
+    #[serde(default = "Display::new")]
+    pub display: Display,
+    #[serde(default = "Logging::new")]
+    pub logging: Logging,
+    #[serde(default = "Integrity::new")]
+    pub integrity: Integrity,
+    #[serde(default = "Dump::new")]
+    pub dump: Dump,
+    #[serde(default)]
+    pub scenes: HashMap<String, Scene>,
+}
+
+impl Config {
+    pub fn new() -> Config {
+        Config {
+            mods: Mods::new(),
+            load_order: Vec::new(),
+            version_policy: VersionPolicy::new(),
+            variants: default_variants(),
+            renderer: Renderer::new(),
+            display: Display::new(),
+            logging: Logging::new(),
+            integrity: Integrity::new(),
+            dump: Dump::new(),
+            scenes: HashMap::new(),
+        }
+    }
+
+    pub fn get() -> Config {
+        CONFIG.read().unwrap().clone()
+    }
+
+    /// The renderer settings for a scene, by its background name, with its `[scenes]` overrides
+    pub fn renderer_for(&self, scene: &str) -> Renderer {
+        let mut renderer = self.renderer.clone();
+        if let Some((_, overrides)) = self
+            .scenes
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(scene))
+        {
+            renderer.hq_assets = overrides.hq_assets.unwrap_or(renderer.hq_assets);
+            renderer.video_cutouts = overrides.video_cutouts.unwrap_or(renderer.video_cutouts);
+            renderer.linear_filtering = overrides
+                .linear_filtering
+                .unwrap_or(renderer.linear_filtering);
+        }
+        renderer
+    }
+
+    /// Loads `grimmod.toml` and applies any overrides from environment variables and
+    /// command line arguments, keeping any problems to be logged by `report`
+    pub fn load() -> Config {
+        let mut diagnostics = DIAGNOSTICS.lock().unwrap();
+        let config = Config::load_file(&mut diagnostics);
+        // `vars` and `args` panic on anything that isn't valid Unicode, which can't
+        // be an override anyway
+        let vars = std::env::vars_os().filter_map(|(name, value)| {
+            Some((name.into_string().ok()?, value.into_string().ok()?))
+        });
+        let args = std::env::args_os().filter_map(|arg| arg.into_string().ok());
+        let overrides = overrides(vars, args);
+        let (config, override_diagnostics) = config.apply(&overrides);
+        diagnostics.extend(override_diagnostics);
+        config
+    }
+
+    /// Loads `grimmod.toml` alone
+    ///
+    /// A documented default config is written if there is none, and settings
+    /// added since an existing config was written are added to it
+    fn load_file(diagnostics: &mut Vec<Diagnostic>) -> Config {
+        let contents = match std::fs::read_to_string(CONFIG_FILENAME) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                diagnostics.push(match std::fs::write(CONFIG_FILENAME, DEFAULT_CONFIG) {
+                    Ok(()) => Diagnostic::Created,
+                    Err(err) => Diagnostic::Unwritable(err.to_string()),
+                });
+                return Config::new();
+            }
+            Err(err) => {
+                diagnostics.push(Diagnostic::Unreadable(err.to_string()));
+                return Config::new();
+            }
+        };
+
+        if let Some((upgraded, added)) = upgrade(&contents) {
+            diagnostics.push(match std::fs::write(CONFIG_FILENAME, upgraded) {
+                Ok(()) => Diagnostic::Upgraded(added),
+                Err(err) => Diagnostic::Unwritable(err.to_string()),
+            });
+        }
+
+        let (config, parse_diagnostics) = Config::parse(&contents);
+        diagnostics.extend(parse_diagnostics);
+        config
+    }
+
+    /// Applies overrides in order, so later ones win. Overrides that don't fit
+    /// the config are skipped
+    pub fn apply(self, overrides: &[Override]) -> (Config, Vec<Diagnostic>) {
+        let mut config = self;
+        let mut diagnostics = Vec::new();
+        for item in overrides {
+            let Ok(toml::Value::Table(mut table)) = toml::Value::try_from(&config) else {
+                break;
+            };
+            set(&mut table, &item.key, item.value.clone());
+
+            let applied = toml::Value::Table(table)
+                .try_into::<Config>()
+                .map_err(|err| err.message().to_string())
+                .and_then(|applied| match toml::Value::try_from(&applied) {
+                    Ok(toml::Value::Table(table)) if get(&table, &item.key).is_some() => {
+                        Ok(applied)
+                    }
+                    _ => Err("unknown setting".to_string()),
+                });
+            match applied {
+                Ok(applied) => {
+                    config = applied;
+                    diagnostics.push(Diagnostic::Overridden(item.to_string()));
+                }
+                Err(message) => diagnostics.push(Diagnostic::BadOverride {
+                    source: item.source.clone(),
+                    message,
+                }),
+            }
+        }
+        (config, diagnostics)
+    }
+
+    /// Parses a config, falling back to defaults for only the settings that are wrong
+    ///
+    /// A syntax error makes the whole file unusable, but a setting with the wrong
+    /// type is blanked out and parsing is retried so the others still apply
+    pub fn parse(contents: &str) -> (Config, Vec<Diagnostic>) {
+        let mut diagnostics = Vec::new();
+        if let Err(err) = contents.parse::<toml::Table>() {
+            let (line, column) = err
+                .span()
+                .map_or((1, 1), |span| location(contents, span.start));
+            let message = err.message().to_string();
+            diagnostics.push(Diagnostic::Syntax {
+                line,
+                column,
+                message,
+            });
+            return (Config::new(), diagnostics);
+        }
+
+        let mut contents = contents.to_string();
+        let config = loop {
+            match toml::from_str::<Config>(&contents) {
+                Ok(config) => break config,
+                Err(err) => {
+                    let message = err.message().to_string();
+                    let Some(span) = err.span() else {
+                        diagnostics.push(Diagnostic::WrongType {
+                            line: 1,
+                            column: 1,
+                            message,
+                        });
+                        break Config::new();
+                    };
+                    let (line, column) = location(&contents, span.start);
+                    diagnostics.push(Diagnostic::WrongType {
+                        line,
+                        column,
+                        message,
+                    });
+                    if !blank_lines(&mut contents, span) {
+                        break Config::new();
+                    }
+                }
+            }
+        };
+
+        // settings blanked out above have been reported already
+        if let (Ok(table), Ok(toml::Value::Table(effective))) = (
+            contents.parse::<toml::Table>(),
+            toml::Value::try_from(&config),
+        ) {
+            for key in unknown_keys(&table, &effective, "") {
+                diagnostics.push(Diagnostic::UnknownKey(key));
+            }
+        }
+
+        (config, diagnostics)
+    }
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config::new()
+    }
+}
+
+/// A setting given outside of `grimmod.toml`, by its dotted key
+pub struct Override {
+    pub key: String,
+    pub value: toml::Value,
+    /// The environment variable or argument it came from
+    pub source: String,
+}
+
+impl std::fmt::Display for Override {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} = {} (from {})", self.key, self.value, self.source)
+    }
+}
+
+/// Collects overrides from `GRIMMOD_*` environment variables followed by `--grimmod-*`
+/// arguments, so arguments take precedence
+///
+/// `GRIMMOD_RENDERER__VIDEO_CUTOUTS=false` and `--grimmod-renderer.video-cutouts=false`
+/// both set `renderer.video_cutouts`. An argument without a value sets `true`
+pub fn overrides<V, A>(vars: V, args: A) -> Vec<Override>
+where
+    V: IntoIterator<Item = (String, String)>,
+    A: IntoIterator<Item = String>,
+{
+    let vars = vars.into_iter().filter_map(|(name, value)| {
+        let key = name
+            .strip_prefix("GRIMMOD_")?
+            .to_lowercase()
+            .replace("__", ".");
+        Some(Override {
+            key,
+            value: parse_value(&value),
+            source: format!("environment variable {}", name),
+        })
+    });
+    let args = args.into_iter().filter_map(|arg| {
+        let setting = arg.strip_prefix("--grimmod-")?;
+        let (key, value) = setting.split_once('=').unwrap_or((setting, "true"));
+        Some(Override {
+            key: key.to_lowercase().replace('-', "_"),
+            value: parse_value(value),
+            source: format!("argument {}", arg),
+        })
+    });
+    vars.chain(args).collect()
+}
+
+/// Reads an override's value as TOML, or as a plain string if it isn't valid TOML
+fn parse_value(value: &str) -> toml::Value {
+    format!("value = {}", value)
+        .parse::<toml::Table>()
+        .ok()
+        .and_then(|mut table| table.remove("value"))
+        .unwrap_or_else(|| toml::Value::String(value.to_string()))
+}
+
+/// Sets a dotted key in a table, turning anything in the way into a table
+fn set(table: &mut toml::Table, key: &str, value: toml::Value) {
+    match key.split_once('.') {
+        None => {
+            table.insert(key.to_string(), value);
+        }
+        Some((first, rest)) => {
+            let entry = table
+                .entry(first)
+                .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+            if !entry.is_table() {
+                *entry = toml::Value::Table(toml::Table::new());
+            }
+            if let toml::Value::Table(table) = entry {
+                set(table, rest, value);
+            }
+        }
+    }
+}
+
+fn get<'a>(table: &'a toml::Table, key: &str) -> Option<&'a toml::Value> {
+    match key.split_once('.') {
+        None => table.get(key),
+        Some((first, rest)) => get(table.get(first)?.as_table()?, rest),
+    }
+}
+
+/// Watches `grimmod.toml` on a background thread, flagging a reload whenever it changes
+///
+/// The reload itself is left to `take_reload`, so it can happen on the game's thread
+pub fn watch() {
+    let watcher = std::thread::Builder::new().name("config-watcher".to_string());
+    let spawned = watcher.spawn(|| {
+        let modified = || {
+            std::fs::metadata(CONFIG_FILENAME)
+                .and_then(|metadata| metadata.modified())
+                .ok()
+        };
+        let mut last_modified: Option<SystemTime> = modified();
+        loop {
+            std::thread::sleep(Duration::from_secs(1));
+            let current = modified();
+            if current != last_modified {
+                last_modified = current;
+                RELOAD_PENDING.store(true, Ordering::Release);
+            }
+        }
+    });
+    if let Err(err) = spawned {
+        debug::error(format!("Could not watch {}: {}", CONFIG_FILENAME, err));
+    }
+}
+
+/// Reloads the config if the file changed since the last call, returning the old and new configs
+pub fn take_reload() -> Option<(Config, Config)> {
+    if !RELOAD_PENDING.swap(false, Ordering::AcqRel) {
+        return None;
+    }
+    Some(reload())
+}
+
+/// Reloads the config from `grimmod.toml`, environment variables and arguments,
+/// returning the old and new configs
+pub fn reload() -> (Config, Config) {
+    let new = Config::load();
+    let old = std::mem::replace(&mut *CONFIG.write().unwrap(), new.clone());
+    debug::configure();
+    debug::event(
+        Level::Info,
+        Subsystem::General,
+        "config_reloaded",
+        debug::json!({ "changed": old != new }),
+        format!("Reloaded {}", CONFIG_FILENAME),
+    );
+    report();
+    (old, new)
+}
+
+/// Logs any problems found in `grimmod.toml` and the configuration in effect
+pub fn report() {
+    for diagnostic in DIAGNOSTICS.lock().unwrap().drain(..) {
+        match diagnostic {
+            Diagnostic::Created | Diagnostic::Upgraded(_) | Diagnostic::Overridden(_) => {
+                debug::info(diagnostic.to_string())
+            }
+            Diagnostic::WrongType { .. }
+            | Diagnostic::UnknownKey(_)
+            | Diagnostic::BadOverride { .. } => debug::warn(diagnostic.to_string()),
+            _ => debug::error(diagnostic.to_string()),
+        };
+    }
+    if let Ok(effective) = toml::to_string(&Config::get()) {
+        debug::info("Effective config:");
+        for line in effective.lines().filter(|line| !line.is_empty()) {
+            debug::info(format!("  {}", line));
+        }
+    }
+}
+
+/// Adds every setting missing from a config, with its comment, from the default config
+///
+/// The user's values, comments and layout are kept as they are. Returns the upgraded
+/// config and the added settings, or `None` if nothing is missing or it isn't valid TOML
+pub fn upgrade(contents: &str) -> Option<(String, Vec<String>)> {
+    let mut document = contents.parse::<toml_edit::DocumentMut>().ok()?;
+    let defaults = DEFAULT_CONFIG.parse::<toml_edit::DocumentMut>().ok()?;
+
+    let mut added = Vec::new();
+    add_missing(document.as_table_mut(), defaults.as_table(), "", &mut added);
+    if added.is_empty() {
+        return None;
+    }
+    Some((document.to_string(), added))
+}
+
+fn add_missing(
+    table: &mut toml_edit::Table,
+    defaults: &toml_edit::Table,
+    prefix: &str,
+    added: &mut Vec<String>,
+) {
+    for (key, default) in defaults.iter() {
+        let path = format!("{}{}", prefix, key);
+        match (table.get_mut(key), default) {
+            (None, _) => {
+                let Some((key, _)) = defaults.get_key_value(key) else {
+                    continue;
+                };
+                let mut default = default.clone();
+                // new tables go after the user's own
+                if let toml_edit::Item::Table(default) = &mut default {
+                    default.set_position(usize::MAX);
+                }
+                table.insert_formatted(key, default);
+                added.push(path);
+            }
+            (Some(toml_edit::Item::Table(table)), toml_edit::Item::Table(defaults)) => {
+                add_missing(table, defaults, &format!("{}.", path), added)
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The 1-based line and column of a byte offset
+fn location(contents: &str, offset: usize) -> (usize, usize) {
+    let before = &contents[..offset.min(contents.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.chars().rev().take_while(|c| *c != '\n').count() + 1;
+    (line, column)
+}
+
+/// Replaces every line a span touches with spaces, keeping line numbers intact,
+/// `false` if there was nothing left to blank
+fn blank_lines(contents: &mut String, span: std::ops::Range<usize>) -> bool {
+    let start = contents[..span.start.min(contents.len())]
+        .rfind('\n')
+        .map_or(0, |i| i + 1);
+    let end = contents[span.end.min(contents.len())..]
+        .find('\n')
+        .map_or(contents.len(), |i| span.end + i);
+    let blanked: String = contents[start..end]
+        .chars()
+        .map(|c| if c == '\n' { c } else { ' ' })
+        .collect();
+    if blanked == contents[start..end] {
+        return false;
+    }
+    contents.replace_range(start..end, &blanked);
+    true
+}
+
+/// Lists the dotted keys set in the file that didn't make it into the parsed config
+fn unknown_keys(table: &toml::Table, effective: &toml::Table, prefix: &str) -> Vec<String> {
+    let mut unknown = Vec::new();
+    for (key, value) in table {
+        let path = format!("{}{}", prefix, key);
+        match (value, effective.get(key)) {
+            (_, None) => unknown.push(path),
+            (toml::Value::Table(table), Some(toml::Value::Table(effective))) => {
+                unknown.extend(unknown_keys(table, effective, &format!("{}.", path)))
+            }
+            _ => {}
+        }
+    }
+    unknown
+}
+
+/// Either `mods = true/false` to switch all mods on or off,
+/// or a `[mods]` table switching individual mods by name
+#[derive(Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(from = "ModsSetting", into = "ModsSetting")]
+pub struct Mods {
+    pub enabled: bool,
+    pub switches: HashMap<String, bool>,
+}
+
+impl Mods {
+    pub fn new() -> Mods {
+        Mods {
+            enabled: true,
+            switches: HashMap::new(),
+        }
+    }
+}
+
+impl Default for Mods {
+    fn default() -> Mods {
+        Mods::new()
+    }
+}
+
+#[derive(serde::Deserialize, serde::Serialize)]
+#[serde(untagged)]
+enum ModsSetting {
+    All(bool),
+    Each(HashMap<String, bool>),
+}
+
+impl From<ModsSetting> for Mods {
+    fn from(setting: ModsSetting) -> Mods {
+        match setting {
+            ModsSetting::All(enabled) => Mods {
+                enabled,
+                switches: HashMap::new(),
+            },
+            ModsSetting::Each(switches) => Mods {
+                enabled: true,
+                switches,
+            },
+        }
+    }
+}
+
+impl From<Mods> for ModsSetting {
+    fn from(mods: Mods) -> ModsSetting {
+        if mods.switches.is_empty() || !mods.enabled {
+            ModsSetting::All(mods.enabled)
+        } else {
+            ModsSetting::Each(mods.switches)
+        }
+    }
+}
+
+/// What to do with mods made for another version of grimmod
+#[derive(Clone, Copy, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VersionPolicy {
+    /// Load them anyway, logging the mismatch
+    Warn,
+    /// Don't load them
+    Disable,
+    /// Don't load them, nor mods without a valid `info.json` to check
+    Strict,
+}
+
+impl VersionPolicy {
+    pub fn new() -> VersionPolicy {
+        VersionPolicy::Warn
+    }
+}
+
+impl Default for VersionPolicy {
+    fn default() -> VersionPolicy {
+        VersionPolicy::new()
+    }
+}
+
+#[derive(Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct Display {
+    #[serde(default = "default_true")]
+    pub hdpi_fix: bool,
+    #[serde(default = "default_true")]
+    pub vsync: bool,
+}
+
+impl Display {
+    pub fn new() -> Display {
+        Display {
+            hdpi_fix: true,
+            vsync: true,
+        }
+    }
+}
+
+impl Default for Display {
+    fn default() -> Display {
+        Display::new()
+    }
+}
+
+#[derive(Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct Renderer {
+    #[serde(default = "default_true")]
+    pub hq_assets: bool,
+    #[serde(default = "default_true")]
+    pub quick_toggle: bool,
+    #[serde(default = "default_true")]
+    pub video_cutouts: bool,
+    #[serde(default = "default_true")]
+    pub linear_filtering: bool,
+}
+
+impl Renderer {
+    pub fn new() -> Renderer {
+        Renderer {
+            hq_assets: true,
+            quick_toggle: true,
+            video_cutouts: true,
+            linear_filtering: true,
+        }
+    }
+}
+
+impl Default for Renderer {
+    fn default() -> Renderer {
+        Renderer::new()
+    }
+}
+
+/// Renderer settings overridden for a single scene, unset ones follow `[renderer]`
+#[derive(Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct Scene {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hq_assets: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub video_cutouts: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub linear_filtering: Option<bool>,
+}
+
+#[derive(Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct Logging {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default = "default_level")]
+    pub level: Level,
+    /// Levels for single subsystems, overriding `level`
+    #[serde(default)]
+    pub subsystems: HashMap<Subsystem, Level>,
+    #[serde(default = "default_false")]
+    pub debug: bool,
+    #[serde(default = "default_false")]
+    pub conflict_report: bool,
+    /// Also write every message as a JSON record to `grimmod.jsonl`
+    #[serde(default = "default_false")]
+    pub json: bool,
+    /// Time every hook and log the timings every minute and on exit
+    #[serde(default = "default_false")]
+    pub profile: bool,
+    /// Write every file the game opens, and where from, to `grimmod-assets.csv`
+    #[serde(default = "default_false")]
+    pub asset_trace: bool,
+    /// How many logs of previous sessions to keep
+    #[serde(default = "default_keep")]
+    pub keep: usize,
+    /// The size in MB a log can grow to before a new one is started, 0 for no limit
+    #[serde(default = "default_max_size")]
+    pub max_size: u64,
+}
+
+impl Logging {
+    pub fn new() -> Logging {
+        Logging {
+            enabled: true,
+            level: default_level(),
+            subsystems: HashMap::new(),
+            debug: false,
+            conflict_report: false,
+            json: false,
+            profile: false,
+            asset_trace: false,
+            keep: default_keep(),
+            max_size: default_max_size(),
+        }
+    }
+}
+
+impl Default for Logging {
+    fn default() -> Logging {
+        Logging::new()
+    }
+}
+
+#[derive(Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct Integrity {
+    #[serde(default = "Verify::new")]
+    pub verify: Verify,
+}
+
+impl Integrity {
+    pub fn new() -> Integrity {
+        Integrity {
+            verify: Verify::new(),
+        }
+    }
+}
+
+impl Default for Integrity {
+    fn default() -> Integrity {
+        Integrity::new()
+    }
+}
+
+/// When to check mod assets against the checksums in their `info.json`
+#[derive(Clone, Copy, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Verify {
+    Off,
+    /// Check every asset before the game starts
+    Startup,
+    /// Check each asset the first time the game opens it
+    Lazy,
+    /// Check every asset and quit without starting the game
+    Only,
+}
+
+impl Verify {
+    pub fn new() -> Verify {
+        Verify::Off
+    }
+}
+
+impl Default for Verify {
+    fn default() -> Verify {
+        Verify::new()
+    }
+}
+
+#[derive(Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct Dump {
+    /// Write every original image the game opens to `Dump/`, for upscaling
+    #[serde(default = "default_false")]
+    pub enabled: bool,
+}
+
+impl Dump {
+    pub fn new() -> Dump {
+        Dump { enabled: false }
+    }
+}
+
+impl Default for Dump {
+    fn default() -> Dump {
+        Dump::new()
+    }
+}
+
+fn default_keep() -> usize {
+    5
+}
+
+fn default_max_size() -> u64 {
+    10
+}
+
+fn default_level() -> Level {
+    Level::Info
+}
+
+fn default_variants() -> Vec<String> {
+    vec!["en".to_string(), "common".to_string()]
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_false() -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_config_matches_defaults() {
+        let (config, diagnostics) = Config::parse(DEFAULT_CONFIG);
+        assert!(diagnostics.is_empty());
+        assert!(config == Config::new());
+    }
+
+    #[test]
+    fn missing_settings_use_defaults() {
+        let config: Config = toml::from_str("[renderer]\nhq_assets = false\n").unwrap();
+        let defaults = Config::new();
+        assert!(!config.renderer.hq_assets);
+        assert_eq!(config.mods.enabled, defaults.mods.enabled);
+        assert_eq!(
+            config.renderer.video_cutouts,
+            defaults.renderer.video_cutouts
+        );
+        assert_eq!(config.display.vsync, defaults.display.vsync);
+    }
+
+    #[test]
+    fn wrong_type_keeps_other_settings() {
+        let (config, diagnostics) =
+            Config::parse("[renderer]\nvideo_cutouts = 3\nquick_toggle = false\n");
+        assert!(config.renderer.video_cutouts);
+        assert!(!config.renderer.quick_toggle);
+        assert!(matches!(
+            diagnostics.as_slice(),
+            [Diagnostic::WrongType { line: 2, .. }]
+        ));
+    }
+
+    #[test]
+    fn syntax_error_uses_defaults() {
+        let (config, diagnostics) = Config::parse("[renderer\nhq_assets = false\n");
+        assert!(config.renderer.hq_assets);
+        assert!(matches!(
+            diagnostics.as_slice(),
+            [Diagnostic::Syntax { .. }]
+        ));
+    }
+
+    #[test]
+    fn unknown_keys_are_reported() {
+        let (_, diagnostics) = Config::parse("[display]\nvsinc = false\n");
+        assert!(matches!(
+            diagnostics.as_slice(),
+            [Diagnostic::UnknownKey(key)] if key == "display.vsinc"
+        ));
+    }
+
+    #[test]
+    fn upgrade_adds_missing_settings() {
+        let (upgraded, added) = upgrade("mods = false\n").unwrap();
+        assert!(added.contains(&"renderer".to_string()));
+        let (config, diagnostics) = Config::parse(&upgraded);
+        assert!(diagnostics.is_empty());
+        assert!(!config.mods.enabled);
+        assert!(upgrade(DEFAULT_CONFIG).is_none());
+    }
+
+    #[test]
+    fn arguments_override_environment() {
+        let vars = [
+            ("GRIMMOD_RENDERER__VIDEO_CUTOUTS", "false"),
+            ("GRIMMOD_DISPLAY__VSYNC", "false"),
+            ("PATH", "/bin"),
+        ]
+        .map(|(name, value)| (name.to_string(), value.to_string()));
+        let args = ["game.exe", "--grimmod-renderer.video-cutouts"].map(String::from);
+
+        let overrides = overrides(vars, args);
+        let keys: Vec<_> = overrides.iter().map(|item| item.key.as_str()).collect();
+        assert_eq!(
+            keys,
+            [
+                "renderer.video_cutouts",
+                "display.vsync",
+                "renderer.video_cutouts"
+            ]
+        );
+
+        let (config, diagnostics) = Config::new().apply(&overrides);
+        assert!(config.renderer.video_cutouts);
+        assert!(!config.display.vsync);
+        assert_eq!(diagnostics.len(), 3);
+    }
+
+    #[test]
+    fn bad_overrides_are_skipped() {
+        let overrides = overrides(
+            [],
+            ["--grimmod-display.vsync=maybe", "--grimmod-nothing=1"].map(String::from),
+        );
+        let (config, diagnostics) = Config::new().apply(&overrides);
+        assert!(config == Config::new());
+        assert!(diagnostics
+            .iter()
+            .all(|diagnostic| matches!(diagnostic, Diagnostic::BadOverride { .. })));
+    }
+}
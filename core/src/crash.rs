@@ -0,0 +1,179 @@
+use std::backtrace::Backtrace;
+use std::collections::HashSet;
+use std::fmt::Write;
+use std::panic::{AssertUnwindSafe, PanicInfo};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, PoisonError};
+
+use crate::debug::{self, json, Level, Subsystem};
+use crate::renderer::image::{BACKGROUND, HQ_IMAGES};
+use crate::{index, mods, platform};
+
+const CRASH_FILENAME: &str = "grimmod-crash.txt";
+// a panic in a hook tends to repeat every frame, e.g. on a lock it poisoned,
+// so only the first few are logged rather than flooding the log
+const LOGGED_PANICS: u64 = 10;
+
+static PANICS: AtomicU64 = AtomicU64::new(0);
+static RECOVERED: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+
+/// Writes a crash report for any panic, on any thread
+///
+/// Only the first panic of a session gets a report and only the first few are logged.
+/// Logging gives up rather than wait, since the panicking thread may hold the log
+pub fn install() {
+    std::panic::set_hook(Box::new(|info| {
+        let count = PANICS.fetch_add(1, Ordering::SeqCst);
+        if count >= LOGGED_PANICS {
+            return;
+        }
+
+        let message = panic_message(info);
+        debug::try_event(
+            Level::Error,
+            Subsystem::General,
+            "panic",
+            json!({ "message": message }),
+            format!("GrimMod panicked: {}", message),
+        );
+        if count + 1 == LOGGED_PANICS {
+            try_error("Further panics are not logged");
+        }
+
+        if count > 0 {
+            return;
+        }
+        match std::fs::write(CRASH_FILENAME, report(&message)) {
+            Ok(()) => try_error(format!("Crash report written to {}", CRASH_FILENAME)),
+            Err(err) => try_error(format!("Could not write {}: {}", CRASH_FILENAME, err)),
+        };
+    }));
+}
+
+fn try_error<T: AsRef<str>>(message: T) -> Option<()> {
+    debug::try_event(
+        Level::Error,
+        Subsystem::General,
+        "log",
+        json!(null),
+        message,
+    )
+}
+
+/// Runs the body of a hook, returning `None` if it panicked so the hook can fall
+/// back to the game's own function instead of unwinding into the game
+///
+/// Only the first recovery in each hook is logged, as it tends to repeat every frame
+pub fn guard<T>(hook: &str, body: impl FnOnce() -> T) -> Option<T> {
+    let result = std::panic::catch_unwind(AssertUnwindSafe(body)).ok();
+    if result.is_none() {
+        let mut recovered = RECOVERED.lock().unwrap_or_else(PoisonError::into_inner);
+        if recovered
+            .get_or_insert_with(HashSet::new)
+            .insert(hook.to_string())
+        {
+            drop(recovered);
+            debug::error(format!(
+                "Recovered from a panic in {}, later ones in it are not logged",
+                hook
+            ));
+        }
+    }
+    result
+}
+
+fn panic_message(info: &PanicInfo) -> String {
+    let payload = info
+        .payload()
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| info.payload().downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string());
+    match info.location() {
+        Some(location) => format!("{} at {}", payload, location),
+        None => payload,
+    }
+}
+
+/// Everything about GrimMod's state that might explain a crash
+///
+/// The state is only read if it isn't locked, since the panicking thread may hold the lock
+fn report(message: &str) -> String {
+    let thread = std::thread::current();
+    let mut report = String::new();
+
+    writeln!(report, "GrimMod {} crash report", mods::VERSION).ok();
+    writeln!(report).ok();
+    writeln!(
+        report,
+        "Panic on thread {}: {}",
+        thread.name().unwrap_or("unnamed"),
+        message
+    )
+    .ok();
+
+    writeln!(report, "\nBacktrace:\n{}", Backtrace::force_capture()).ok();
+
+    writeln!(report, "Bound functions:").ok();
+    for (name, addr) in platform::get().bound_fns() {
+        writeln!(report, "  {} 0x{:x}", name, addr).ok();
+    }
+
+    writeln!(report, "\nBackground:").ok();
+    match BACKGROUND.try_lock() {
+        Ok(background) => match background.as_ref() {
+            Some(background) => writeln!(
+                report,
+                "  {} ({}x{}, scale {})",
+                background.name, background.width, background.height, background.scale
+            ),
+            None => writeln!(report, "  none"),
+        },
+        Err(_) => writeln!(report, "  (locked)"),
+    }
+    .ok();
+
+    writeln!(report, "\nHQ images:").ok();
+    match HQ_IMAGES.try_lock() {
+        Ok(hq_image_containers) => {
+            for hq_image_container in hq_image_containers.iter() {
+                writeln!(
+                    report,
+                    "  {} 0x{:x}",
+                    hq_image_container.name,
+                    hq_image_container.original_addr.underlying()
+                )
+                .ok();
+                for hq_image in hq_image_container.images.iter() {
+                    writeln!(
+                        report,
+                        "    {} 0x{:x} ({}x{}, scale {})",
+                        hq_image.name,
+                        hq_image.original_addr.underlying(),
+                        hq_image.width,
+                        hq_image.height,
+                        hq_image.scale
+                    )
+                    .ok();
+                }
+            }
+        }
+        Err(_) => {
+            writeln!(report, "  (locked)").ok();
+        }
+    }
+
+    writeln!(report, "\nActive mods:").ok();
+    match index::try_mods() {
+        Some(mods) => {
+            for modded in mods {
+                writeln!(report, "  {}", modded).ok();
+            }
+        }
+        None => {
+            writeln!(report, "  (locked)").ok();
+        }
+    }
+
+    report
+}
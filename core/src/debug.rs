@@ -0,0 +1,360 @@
+use once_cell::sync::Lazy;
+use serde_json::Value;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicU8, Ordering};
+use std::sync::{Mutex, PoisonError, TryLockError};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config::Config;
+
+pub use serde_json::json;
+
+const LOG_FILENAME: &str = "grimmod.log";
+const JSON_FILENAME: &str = "grimmod.jsonl";
+
+// the previous session's log is kept rather than truncated, in case it's from a crash
+static LOG_FILE: Lazy<Mutex<Option<LogFile>>> =
+    Lazy::new(|| Mutex::new(LogFile::start(LOG_FILENAME)));
+static JSON_FILE: Lazy<Mutex<Option<LogFile>>> =
+    Lazy::new(|| Mutex::new(LogFile::start(JSON_FILENAME)));
+
+// the logging settings are checked several times a frame, so they're kept apart from
+// the config. Each subsystem's level is stored one higher, leaving 0 for nothing logged
+static CONFIGURED: AtomicBool = AtomicBool::new(false);
+static LEVELS: [AtomicU8; 6] = [
+    AtomicU8::new(0),
+    AtomicU8::new(0),
+    AtomicU8::new(0),
+    AtomicU8::new(0),
+    AtomicU8::new(0),
+    AtomicU8::new(0),
+];
+static JSON: AtomicBool = AtomicBool::new(false);
+static MAX_SIZE: AtomicU64 = AtomicU64::new(0);
+
+struct LogFile {
+    file: File,
+    size: u64,
+}
+
+impl LogFile {
+    /// Moves the logs of earlier sessions along, `grimmod.log` to `grimmod.1.log` and
+    /// so on, dropping any past `logging.keep`, then starts the log of this session
+    ///
+    /// The older part of a session's log (see `split`) moves along with it, so
+    /// `grimmod.log.1` becomes `grimmod.1.log.1`
+    fn start(filename: &str) -> Option<LogFile> {
+        let keep = Config::get().logging.keep;
+        let (stem, extension) = filename.rsplit_once('.')?;
+        let session = |n: usize| format!("{}.{}.{}", stem, n, extension);
+        let remove = |filename: &str| {
+            let removed = std::fs::remove_file(filename).is_ok();
+            std::fs::remove_file(older_part(filename)).is_ok() || removed
+        };
+        let shift = |from: &str, to: &str| {
+            std::fs::rename(from, to).ok();
+            std::fs::rename(older_part(from), older_part(to)).ok();
+        };
+
+        let mut stale = keep.max(1);
+        while remove(&session(stale)) {
+            stale += 1;
+        }
+        for n in (1..keep).rev() {
+            shift(&session(n), &session(n + 1));
+        }
+        if keep > 0 {
+            shift(filename, &session(1));
+        } else {
+            std::fs::remove_file(older_part(filename)).ok();
+        }
+
+        LogFile::create(filename)
+    }
+
+    /// Moves this session's log to `grimmod.log.1`, replacing any older part, and
+    /// carries on in a new one, leaving the logs of earlier sessions alone
+    fn split(filename: &str) -> Option<LogFile> {
+        std::fs::rename(filename, older_part(filename)).ok();
+        LogFile::create(filename)
+    }
+
+    fn create(filename: &str) -> Option<LogFile> {
+        let file = OpenOptions::new()
+            .write(true)
+            .truncate(true)
+            .create(true)
+            .open(filename)
+            .ok()?;
+        Some(LogFile { file, size: 0 })
+    }
+
+    fn write(&mut self, line: &str) -> Option<()> {
+        writeln!(self.file, "{}", line).ok()?;
+        self.size += line.len() as u64 + 1;
+        Some(())
+    }
+}
+
+/// The name of the older part of a session's log, once it outgrew `logging.max_size`
+fn older_part(filename: &str) -> String {
+    format!("{}.1", filename)
+}
+
+/// How important a log message is, each level includes the ones before it
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, serde::Deserialize, serde::Serialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Error,
+    Warn,
+    Info,
+    /// Occasional details, like each modded file being opened
+    Debug,
+    /// Per-frame details
+    Trace,
+}
+
+impl Level {
+    fn label(self) -> &'static str {
+        match self {
+            Level::Error => "ERROR",
+            Level::Warn => "WARN",
+            Level::Info => "INFO",
+            Level::Debug => "DEBUG",
+            Level::Trace => "TRACE",
+        }
+    }
+}
+
+/// The part of GrimMod a log message comes from, which can be filtered on its own
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Subsystem {
+    General,
+    File,
+    HqImages,
+    Animation,
+    Cutouts,
+    Hooks,
+}
+
+impl Subsystem {
+    const ALL: [Subsystem; 6] = [
+        Subsystem::General,
+        Subsystem::File,
+        Subsystem::HqImages,
+        Subsystem::Animation,
+        Subsystem::Cutouts,
+        Subsystem::Hooks,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            Subsystem::General => "general",
+            Subsystem::File => "file",
+            Subsystem::HqImages => "hq_images",
+            Subsystem::Animation => "animation",
+            Subsystem::Cutouts => "cutouts",
+            Subsystem::Hooks => "hooks",
+        }
+    }
+}
+
+/// Picks up the `logging` settings, done on first use and again whenever the config changes
+pub fn configure() {
+    let logging = Config::get().logging;
+    // `logging.debug` predates levels and still turns on debug output everywhere
+    let level = if logging.debug {
+        logging.level.max(Level::Debug)
+    } else {
+        logging.level
+    };
+    for subsystem in Subsystem::ALL {
+        let max_level = logging.subsystems.get(&subsystem).copied().unwrap_or(level);
+        let stored = if logging.enabled {
+            max_level as u8 + 1
+        } else {
+            0
+        };
+        LEVELS[subsystem as usize].store(stored, Ordering::Relaxed);
+    }
+    JSON.store(logging.json, Ordering::Relaxed);
+    MAX_SIZE.store(logging.max_size * 1024 * 1024, Ordering::Relaxed);
+    CONFIGURED.store(true, Ordering::Release);
+}
+
+/// Checks whether a message would be logged, to skip building messages that wouldn't be
+pub fn enabled(level: Level, subsystem: Subsystem) -> bool {
+    if !CONFIGURED.load(Ordering::Acquire) {
+        configure();
+    }
+    (level as u8) < LEVELS[subsystem as usize].load(Ordering::Relaxed)
+}
+
+pub fn log<T: AsRef<str>>(level: Level, subsystem: Subsystem, message: T) -> Option<()> {
+    event(level, subsystem, "log", Value::Null, message)
+}
+
+/// Logs a message that tools can pick out of `grimmod.jsonl` by its event type and fields
+///
+/// The human log only gets the message, the event type and fields must stay stable
+pub fn event<T: AsRef<str>>(
+    level: Level,
+    subsystem: Subsystem,
+    event: &str,
+    fields: Value,
+    message: T,
+) -> Option<()> {
+    if !enabled(level, subsystem) {
+        return None;
+    }
+    record(level, subsystem, event, fields, message.as_ref(), true)
+}
+
+/// Like `event`, but skips the message rather than wait for a log that's in use
+///
+/// Meant for the panic hook, where the panicking thread may be the one holding it
+pub(crate) fn try_event<T: AsRef<str>>(
+    level: Level,
+    subsystem: Subsystem,
+    event: &str,
+    fields: Value,
+    message: T,
+) -> Option<()> {
+    // configuring reads the config, which may be locked too
+    if !CONFIGURED.load(Ordering::Acquire) || !enabled(level, subsystem) {
+        return None;
+    }
+    record(level, subsystem, event, fields, message.as_ref(), false)
+}
+
+fn record(
+    level: Level,
+    subsystem: Subsystem,
+    event: &str,
+    fields: Value,
+    message: &str,
+    wait: bool,
+) -> Option<()> {
+    let time = timestamp();
+    let thread = std::thread::current();
+    let thread_name = match thread.name() {
+        Some(name) => name.to_string(),
+        None => format!("{:?}", thread.id()),
+    };
+
+    if JSON.load(Ordering::Relaxed) {
+        let record = json!({
+            "time": time,
+            "level": level,
+            "thread": thread_name,
+            "subsystem": subsystem,
+            "event": event,
+            "message": message,
+            "fields": fields,
+        });
+        append(&JSON_FILE, JSON_FILENAME, &record.to_string(), wait);
+    }
+
+    let line = format!(
+        "{} {:<5} [{}] {}: {}",
+        time,
+        level.label(),
+        thread_name,
+        subsystem.label(),
+        message
+    );
+    append(&LOG_FILE, LOG_FILENAME, &line, wait)
+}
+
+/// Writes a line to a log, or gives up if `wait` is off and the log is in use
+///
+/// A log whose lock was poisoned by a panic mid-write is still used, at worst
+/// it has a partial line in it
+fn append(
+    log_file: &Lazy<Mutex<Option<LogFile>>>,
+    filename: &str,
+    line: &str,
+    wait: bool,
+) -> Option<()> {
+    let mut log_file = if wait {
+        log_file.lock().unwrap_or_else(PoisonError::into_inner)
+    } else {
+        match Lazy::get(log_file)?.try_lock() {
+            Ok(log_file) => log_file,
+            Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner(),
+            Err(TryLockError::WouldBlock) => return None,
+        }
+    };
+
+    // a session that outgrows the cap carries on in a new log
+    let max_size = MAX_SIZE.load(Ordering::Relaxed);
+    if wait
+        && max_size > 0
+        && log_file
+            .as_ref()
+            .is_some_and(|log_file| log_file.size > max_size)
+    {
+        log_file.take();
+        *log_file = LogFile::split(filename);
+        if filename == LOG_FILENAME
+            && let Some(log_file) = log_file.as_mut()
+        {
+            log_file.write(&format!("(continued from {})", older_part(filename)));
+        }
+    }
+
+    log_file.as_mut()?.write(line)
+}
+
+pub fn info<T: AsRef<str>>(message: T) -> Option<()> {
+    log(Level::Info, Subsystem::General, message)
+}
+
+pub fn warn<T: AsRef<str>>(message: T) -> Option<()> {
+    log(Level::Warn, Subsystem::General, message)
+}
+
+pub fn error<T: AsRef<str>>(message: T) -> Option<()> {
+    log(Level::Error, Subsystem::General, message)
+}
+
+/// The current UTC time as `YYYY-MM-DD HH:MM:SS.mmm`
+pub(crate) fn timestamp() -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    let seconds = now.as_secs();
+    let (hours, minutes, seconds_of_minute) =
+        ((seconds / 3600) % 24, (seconds / 60) % 60, seconds % 60);
+
+    // days since the epoch to a civil date, from Howard Hinnant's algorithms
+    let days = (seconds / 86400) as i64 + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}.{:03}",
+        year,
+        month,
+        day,
+        hours,
+        minutes,
+        seconds_of_minute,
+        now.subsec_millis()
+    )
+}
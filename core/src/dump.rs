@@ -0,0 +1,147 @@
+use image::RgbaImage;
+use once_cell::sync::Lazy;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::Mutex;
+use std::thread;
+
+use crate::bm::{Bm, BmFormat};
+use crate::config::Config;
+use crate::debug::{self, json, Level, Subsystem};
+use crate::lab::Labs;
+use crate::renderer::graphics::{ImageAddr, ImageContainer};
+
+const DUMP_DIR: &str = "Dump";
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static LABS: Lazy<Labs> = Lazy::new(Labs::open);
+// containers already dumped (or being dumped), by their lowercase name
+static DUMPED: Lazy<Mutex<HashSet<String>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+// the container of every image seen opened, to find the file an image came from
+static CONTAINERS: Lazy<Mutex<HashMap<ImageAddr, String>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+// decoding and encoding is slow, so containers are dumped one at a time off the game's thread
+static WORKER: Lazy<Sender<String>> = Lazy::new(|| {
+    let (sender, receiver) = mpsc::channel::<String>();
+    thread::spawn(move || {
+        for name in receiver {
+            report(&name, write(&name));
+        }
+    });
+    sender
+});
+
+/// Picks up `dump.enabled`, kept apart from the config as images are decompressed every frame
+pub fn configure() {
+    ENABLED.store(Config::get().dump.enabled, Ordering::Relaxed);
+}
+
+/// Dumps the images of a container the game just opened, if dumping is enabled
+pub fn container(image_container: &ImageContainer) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+
+    let name = image_container.name().to_lowercase();
+    let mut containers = CONTAINERS.lock().unwrap();
+    for image in image_container.images.iter() {
+        containers.insert(image.addr, name.clone());
+    }
+    drop(containers);
+
+    dump(name);
+}
+
+/// Dumps the container of an image the game is decompressing, if it hasn't been already
+pub fn image(image_addr: ImageAddr) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+
+    let name = CONTAINERS.lock().unwrap().get(&image_addr).cloned();
+    if let Some(name) = name {
+        dump(name);
+    }
+}
+
+fn dump(name: String) {
+    // depth maps and anything else without a HQ replacement aren't worth dumping
+    if !name.ends_with(".bm") || !DUMPED.lock().unwrap().insert(name.clone()) {
+        return;
+    }
+
+    // the worker never exits, so the send can't fail
+    let _ = WORKER.send(name);
+}
+
+fn report(name: &str, result: Result<usize, String>) {
+    match result {
+        Ok(frames) => {
+            if debug::enabled(Level::Debug, Subsystem::HqImages) {
+                debug::event(
+                    Level::Debug,
+                    Subsystem::HqImages,
+                    "image_dumped",
+                    json!({ "container": name, "frames": frames }),
+                    format!("Dumped {} ({} frames)", name, frames),
+                );
+            }
+        }
+        Err(err) => {
+            debug::event(
+                Level::Error,
+                Subsystem::HqImages,
+                "image_dump_failed",
+                json!({ "container": name, "error": err }),
+                format!("Could not dump {}: {}", name, err),
+            );
+        }
+    }
+}
+
+/// Writes every frame of a bitmap from the game's LAB archives to `Dump/<name>_<index>.png`,
+/// with a `Dump/<name>.json` describing them, returning the number of frames
+fn write(name: &str) -> Result<usize, String> {
+    let (lab, entry) = LABS
+        .find(name)
+        .ok_or_else(|| "not found in the LAB archives".to_string())?;
+    let bytes = lab.read(&entry.name).map_err(|err| err.to_string())?;
+    let bm = Bm::parse(&bytes).map_err(|err| err.to_string())?;
+    if bm.header.format != BmFormat::Color {
+        return Err("not a color bitmap".to_string());
+    }
+
+    let stem = name.trim_end_matches(".bm");
+    let dir = Path::new(DUMP_DIR);
+    std::fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+
+    for (index, image) in bm.images.iter().enumerate() {
+        let buffer = bm
+            .rgba(index)
+            .ok_or_else(|| format!("frame {} could not be decoded", index))?;
+        let len = buffer.len();
+        let png = RgbaImage::from_raw(image.width, image.height, buffer).ok_or_else(|| {
+            format!(
+                "frame {} has {} bytes of pixels for {}x{}",
+                index, len, image.width, image.height
+            )
+        })?;
+        png.save(dir.join(format!("{}_{}.png", stem, index)))
+            .map_err(|err| err.to_string())?;
+    }
+
+    let sidecar = json!({
+        "container": name,
+        "width": bm.header.width,
+        "height": bm.header.height,
+        "x": bm.header.x,
+        "y": bm.header.y,
+        "frames": bm.images.len(),
+    });
+    std::fs::write(dir.join(format!("{}.json", stem)), sidecar.to_string())
+        .map_err(|err| err.to_string())?;
+
+    Ok(bm.images.len())
+}
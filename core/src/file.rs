@@ -0,0 +1,229 @@
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+
+use std::ffi::{c_char, c_void, CStr};
+use std::fs::File;
+use std::io::{self, BufReader, Cursor, Read, Seek};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::archive::ModArchive;
+use crate::debug::{self, json, Level, Subsystem};
+use crate::trace::{self, Source};
+use crate::vfs::{self, VirtualFile};
+use crate::{crash, index, platform, profile};
+
+/// A byte source that can be read from any position
+pub trait ReadSeek: Read + Seek + Send {}
+
+impl<T: Read + Seek + Send> ReadSeek for T {}
+
+/// A modded resource, either loose in a mod folder or packed in a mod archive
+#[derive(Clone)]
+pub enum ModdedFile {
+    Loose(PathBuf),
+    Packed {
+        archive: Arc<ModArchive>,
+        entry: String,
+    },
+}
+
+impl ModdedFile {
+    /// Reads the whole file into memory
+    pub fn read(&self) -> io::Result<Vec<u8>> {
+        match self {
+            ModdedFile::Loose(path) => std::fs::read(path),
+            ModdedFile::Packed { archive, entry } => archive.read(entry),
+        }
+    }
+
+    /// Opens the file for streaming, archive entries are read into memory first
+    pub fn open(&self) -> io::Result<Box<dyn ReadSeek>> {
+        match self {
+            ModdedFile::Loose(path) => Ok(Box::new(BufReader::new(File::open(path)?))),
+            ModdedFile::Packed { .. } => Ok(Box::new(Cursor::new(self.read()?))),
+        }
+    }
+
+    /// The file's path inside its mod's `assets` folder in lowercase, e.g. `common/foo.png`
+    pub fn asset_path(&self) -> Option<String> {
+        let path = match self {
+            ModdedFile::Loose(path) => path.to_str()?,
+            ModdedFile::Packed { entry, .. } => entry.as_str(),
+        };
+        let mut components = path.rsplit(['/', '\\']);
+        let filename = components.next()?;
+        let variant = components.next()?;
+        Some(format!("{}/{}", variant, filename).to_lowercase())
+    }
+
+    pub fn display(&self) -> String {
+        match self {
+            ModdedFile::Loose(path) => path.display().to_string(),
+            ModdedFile::Packed { archive, entry } => {
+                format!("{}:{}", archive.path.display(), entry)
+            }
+        }
+    }
+}
+
+/// Finds a modded resource, if one exists
+pub fn find_modded(filename: &str) -> Option<ModdedFile> {
+    index::get(filename)
+}
+
+/// Enhances the game's open file function, opening modded files if found
+pub extern "C" fn open(raw_filename: *mut c_char, mode: *mut c_char) -> *mut c_void {
+    let _timer = profile::timer("open_file");
+    crash::guard("open_file", || open_modded(raw_filename, mode))
+        .unwrap_or_else(|| platform::get().open_file(raw_filename, mode))
+}
+
+fn open_modded(raw_filename: *mut c_char, mode: *mut c_char) -> *mut c_void {
+    let Ok(filename) = unsafe { CStr::from_ptr(raw_filename) }.to_str() else {
+        return std::ptr::null_mut();
+    };
+    let mode_str = unsafe { CStr::from_ptr(mode) }.to_string_lossy();
+
+    // only reads can be served from mods, anything else is left to the game
+    if mode_str.contains(['w', 'a', '+']) {
+        return open_original(filename, raw_filename, mode);
+    }
+
+    let Some(modded) = find_modded(filename) else {
+        return open_original(filename, raw_filename, mode);
+    };
+
+    if debug::enabled(Level::Debug, Subsystem::File) {
+        debug::event(
+            Level::Debug,
+            Subsystem::File,
+            "asset_opened",
+            json!({ "asset": filename, "path": modded.display() }),
+            format!("Opening modded {} file", modded.display()),
+        );
+    }
+
+    match modded.open() {
+        Ok(source) => {
+            let provider = index::provider(filename).unwrap_or_default();
+            trace::record(filename, Source::Mod(&provider));
+            vfs::open(VirtualFile::new(filename, source))
+        }
+        Err(err) => {
+            debug::event(
+                Level::Error,
+                Subsystem::File,
+                "asset_open_failed",
+                json!({ "path": modded.display(), "error": err.to_string() }),
+                format!("Could not open {}: {}", modded.display(), err),
+            );
+            open_original(filename, raw_filename, mode)
+        }
+    }
+}
+
+/// Opens a file through the game's own functions, which look in its LAB archives
+fn open_original(filename: &str, raw_filename: *mut c_char, mode: *mut c_char) -> *mut c_void {
+    let file = platform::get().open_file(raw_filename, mode);
+    let source = if file.is_null() {
+        Source::Missing
+    } else {
+        Source::Lab
+    };
+    trace::record(filename, source);
+    file
+}
+
+/// Closes original or modded files
+pub extern "C" fn close(file: *mut c_void) -> i32 {
+    let _timer = profile::timer("close_file");
+    match crash::guard("close_file", || vfs::close(file)) {
+        Some(true) => 0,
+        Some(false) => platform::get().close_file(file),
+        // the file may be a modded one, which the game can't close
+        None => -1,
+    }
+}
+
+/// Reads from original or modded files
+pub extern "C" fn read(file: *mut c_void, dst: *mut c_void, size: usize) -> usize {
+    let _timer = profile::timer("read_file");
+    match crash::guard("read_file", || vfs::is_open(file)) {
+        Some(true) => {}
+        Some(false) => return platform::get().read_file(file, dst, size),
+        // the file may be a modded one, which the game can't read
+        None => return 0,
+    }
+
+    if dst.is_null() || size == 0 {
+        return 0;
+    }
+    let dst = unsafe { std::slice::from_raw_parts_mut(dst as *mut u8, size) };
+    crash::guard("read_file", || vfs::read(file, dst))
+        .flatten()
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::Platform;
+    use std::ffi::c_int;
+
+    // a handle the mock game hands out, which can't clash with a boxed virtual file
+    const GAME_FILE: usize = 0x10;
+
+    struct Game;
+
+    impl Platform for Game {
+        fn open_file(&self, _filename: *mut c_char, _mode: *mut c_char) -> *mut c_void {
+            GAME_FILE as *mut c_void
+        }
+
+        fn close_file(&self, file: *mut c_void) -> c_int {
+            if file as usize == GAME_FILE {
+                0
+            } else {
+                -1
+            }
+        }
+
+        fn read_file(&self, _file: *mut c_void, dst: *mut c_void, size: usize) -> usize {
+            unsafe { std::ptr::write_bytes(dst as *mut u8, 0xaa, size) };
+            size
+        }
+
+        fn bind_cutouts(&self, _triangles: &'static [f32]) {}
+
+        fn bound_fns(&self) -> Vec<(&'static str, usize)> {
+            Vec::new()
+        }
+    }
+
+    #[test]
+    fn virtual_files_are_read_and_closed() {
+        platform::install(Game);
+        let handle = vfs::open(VirtualFile::from_bytes("a.txt", b"hello".to_vec()));
+
+        let mut buffer = [0u8; 8];
+        assert_eq!(read(handle, std::ptr::null_mut(), 4), 0);
+        assert_eq!(read(handle, buffer.as_mut_ptr() as *mut c_void, 0), 0);
+        assert_eq!(read(handle, buffer.as_mut_ptr() as *mut c_void, 3), 3);
+        assert_eq!(read(handle, buffer[3..].as_mut_ptr() as *mut c_void, 5), 2);
+        assert_eq!(&buffer[..5], b"hello");
+
+        assert_eq!(close(handle), 0);
+        assert!(!vfs::is_open(handle));
+    }
+
+    #[test]
+    fn game_files_go_to_the_game() {
+        platform::install(Game);
+        let handle = GAME_FILE as *mut c_void;
+
+        let mut buffer = [0u8; 4];
+        assert_eq!(read(handle, buffer.as_mut_ptr() as *mut c_void, 4), 4);
+        assert_eq!(buffer, [0xaa; 4]);
+        assert_eq!(close(handle), 0);
+    }
+}
@@ -0,0 +1,154 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
+
+use crate::config::{Config, Verify};
+use crate::debug::{self, json, Level, Subsystem};
+use crate::file::ModdedFile;
+use crate::integrity;
+use crate::mods::{self, Mod};
+
+static INDEX: RwLock<Option<ModIndex>> = RwLock::new(None);
+static LAZY_VERIFY: AtomicBool = AtomicBool::new(false);
+
+/// Every modded asset, keyed by its lowercase filename
+///
+/// Built once so the open file hook only needs a single lookup
+/// instead of searching the mods folder every time the game opens a file
+pub struct ModIndex {
+    assets: HashMap<String, ModdedFile>,
+    checksums: HashMap<String, String>,
+    providers: HashMap<String, String>,
+    /// The indexed mods in load order, with their versions, for crash reports
+    mods: Vec<String>,
+}
+
+impl ModIndex {
+    /// Indexes the assets of mods in load order, later mods override earlier ones
+    pub fn build(mods: &[Mod]) -> ModIndex {
+        let mut assets = HashMap::new();
+        let mut checksums = HashMap::new();
+        let mut providers = HashMap::new();
+
+        let names: Vec<_> = mods.iter().map(|modded| modded.name.as_str()).collect();
+        let versioned = mods
+            .iter()
+            .map(|modded| match modded.version() {
+                Some(version) => format!("{} {}", modded.name, version),
+                None => modded.name.clone(),
+            })
+            .collect();
+        debug::event(
+            Level::Info,
+            Subsystem::General,
+            "load_order",
+            json!({ "mods": names }),
+            format!("Mod load order: {}", names.join(", ")),
+        );
+
+        for modded in mods {
+            let mod_assets = modded.assets();
+            debug::event(
+                Level::Info,
+                Subsystem::General,
+                "mod_indexed",
+                json!({ "mod": modded.name, "assets": mod_assets.len() }),
+                format!(
+                    "Indexed {} assets from mod {}",
+                    mod_assets.len(),
+                    modded.name
+                ),
+            );
+            for (filename, file) in mod_assets {
+                match modded.checksum(&file) {
+                    Some(checksum) => checksums.insert(filename.clone(), checksum.to_string()),
+                    None => checksums.remove(&filename),
+                };
+                providers.insert(filename.clone(), modded.name.clone());
+                assets.insert(filename, file);
+            }
+        }
+
+        ModIndex {
+            assets,
+            checksums,
+            providers,
+            mods: versioned,
+        }
+    }
+
+    pub fn get(&self, filename: &str) -> Option<&ModdedFile> {
+        self.assets.get(&filename.to_lowercase())
+    }
+
+    /// The checksum the winning mod lists for an asset, if any
+    pub fn checksum(&self, filename: &str) -> Option<&str> {
+        self.checksums
+            .get(&filename.to_lowercase())
+            .map(String::as_str)
+    }
+
+    /// The name of the mod an asset is served from
+    pub fn provider(&self, filename: &str) -> Option<&str> {
+        self.providers
+            .get(&filename.to_lowercase())
+            .map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.assets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.assets.is_empty()
+    }
+}
+
+/// Picks up `integrity.verify`, kept apart from the config as it's checked on every open
+pub fn configure() {
+    let lazy = Config::get().integrity.verify == Verify::Lazy;
+    LAZY_VERIFY.store(lazy, Ordering::Relaxed);
+}
+
+/// Rebuilds the index from mods in load order, as returned by `mods::load`
+pub fn rebuild(mods: &[Mod]) {
+    let index = ModIndex::build(mods);
+    debug::info(format!("Indexed {} modded assets", index.len()));
+    *INDEX.write().unwrap() = Some(index);
+}
+
+/// Finds the modded file for an asset, building the index first if needed
+///
+/// With lazy verification on, the file is checked against its checksum the first time
+pub fn get(filename: &str) -> Option<ModdedFile> {
+    if INDEX.read().unwrap().is_none() {
+        rebuild(&mods::load());
+    }
+    let index = INDEX.read().unwrap();
+    let index = index.as_ref()?;
+    let file = index.get(filename)?.clone();
+    if LAZY_VERIFY.load(Ordering::Relaxed)
+        && let Some(expected) = index.checksum(filename)
+    {
+        integrity::verify_once(&file, expected);
+    }
+    Some(file)
+}
+
+/// The name of the mod an asset is served from, without building the index
+pub fn provider(filename: &str) -> Option<String> {
+    let index = INDEX.read().unwrap();
+    index.as_ref()?.provider(filename).map(str::to_string)
+}
+
+/// The mods the index was built from, without building it or waiting for it
+///
+/// `None` while the index is being rebuilt, so it's safe to call while panicking
+pub fn try_mods() -> Option<Vec<String>> {
+    let index = INDEX.try_read().ok()?;
+    Some(
+        index
+            .as_ref()
+            .map_or_else(Vec::new, |index| index.mods.clone()),
+    )
+}
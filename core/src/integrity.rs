@@ -0,0 +1,108 @@
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fmt::Write;
+use std::sync::Mutex;
+
+use crate::debug::{self, json, Level, Subsystem};
+use crate::file::ModdedFile;
+use crate::mods::Mod;
+
+static VERIFIED: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+
+/// The lowercase hex SHA-256 digest of some bytes
+pub fn sha256(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .fold(String::new(), |mut digest, byte| {
+            write!(digest, "{:02x}", byte).ok();
+            digest
+        })
+}
+
+/// Checks a modded file against its expected checksum, logging any mismatch
+pub fn verify(file: &ModdedFile, expected: &str) -> bool {
+    match file.read() {
+        Ok(bytes) => {
+            let found = sha256(&bytes);
+            let matches = found.eq_ignore_ascii_case(expected.trim());
+            if !matches {
+                debug::event(
+                    Level::Error,
+                    Subsystem::File,
+                    "checksum_mismatch",
+                    json!({ "path": file.display(), "expected": expected, "found": found }),
+                    format!(
+                        "Checksum mismatch for {}: expected {} but found {}",
+                        file.display(),
+                        expected,
+                        found
+                    ),
+                );
+            }
+            matches
+        }
+        Err(err) => {
+            debug::error(format!("Could not verify {}: {}", file.display(), err));
+            false
+        }
+    }
+}
+
+/// Checks a modded file the first time it's opened, later calls do nothing
+pub fn verify_once(file: &ModdedFile, expected: &str) {
+    let first = VERIFIED
+        .lock()
+        .unwrap()
+        .get_or_insert_with(HashSet::new)
+        .insert(file.display());
+    if first {
+        verify(file, expected);
+    }
+}
+
+/// Checks every asset of every loaded mod that lists checksums, returning the number of failures
+///
+/// Listed assets that are missing from a mod are failures too, as they usually mean
+/// the mod wasn't fully extracted
+pub fn verify_mods(mods: &[Mod]) -> usize {
+    let mut failures = 0;
+    for modded in mods {
+        let Some(info) = &modded.info else {
+            continue;
+        };
+        if info.checksums.is_empty() {
+            continue;
+        }
+
+        let files = modded.variants();
+        for path in info.checksums.keys() {
+            let path = path.replace('\\', "/").to_lowercase();
+            if !files
+                .iter()
+                .any(|(_, _, file)| file.asset_path().as_ref() == Some(&path))
+            {
+                debug::event(
+                    Level::Error,
+                    Subsystem::File,
+                    "asset_missing",
+                    json!({ "mod": modded.name, "asset": path }),
+                    format!("Mod {} is missing its asset {}", modded.name, path),
+                );
+                failures += 1;
+            }
+        }
+        for (_, _, file) in files.iter() {
+            if let Some(expected) = modded.checksum(file)
+                && !verify(file, expected)
+            {
+                failures += 1;
+            }
+        }
+        debug::info(format!(
+            "Verified {} checksums of mod {}",
+            info.checksums.len(),
+            modded.name
+        ));
+    }
+    failures
+}
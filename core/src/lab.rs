@@ -0,0 +1,326 @@
+use glob::{glob_with, MatchOptions};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek, SeekFrom, Take, Write};
+use std::path::{Path, PathBuf};
+
+const MAGIC: &[u8; 4] = b"LABN";
+const HEADER_SIZE: usize = 16;
+const ENTRY_SIZE: usize = 16;
+
+/// A single file packed inside a LAB archive
+#[derive(Clone, Debug)]
+pub struct LabEntry {
+    pub name: String,
+    pub offset: u32,
+    pub size: u32,
+}
+
+/// A LAB datapack, the archive format the game loads all its assets from
+///
+/// The layout is a 16 byte header (`LABN`, version, entry count, name table size),
+/// followed by a 16 byte record per entry (name offset, data offset, size, reserved)
+/// and then a table of null terminated names. All values are little endian.
+pub struct Lab {
+    pub path: PathBuf,
+    entries: Vec<LabEntry>,
+    lookup: HashMap<String, usize>,
+}
+
+impl Lab {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Lab, LabError> {
+        let file = File::open(&path)?;
+        let size = file.metadata()?.len();
+        let entries = Lab::parse(&mut BufReader::new(file), size)?;
+        let lookup = entries
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| (entry.name.to_lowercase(), i))
+            .collect();
+
+        Ok(Lab {
+            path: path.as_ref().to_owned(),
+            entries,
+            lookup,
+        })
+    }
+
+    /// Reads the directory of a LAB archive
+    ///
+    /// `max_size` is the size of the whole archive, a directory claiming to be larger
+    /// is rejected before anything is allocated for it
+    pub fn parse<R: Read>(reader: &mut R, max_size: u64) -> Result<Vec<LabEntry>, LabError> {
+        let mut header = [0u8; HEADER_SIZE];
+        reader.read_exact(&mut header)?;
+        if &header[0..4] != MAGIC {
+            return Err(LabError::InvalidHeader);
+        }
+        let entry_count = read_u32(&header, 8) as usize;
+        let names_size = read_u32(&header, 12) as usize;
+
+        let records_size = entry_count
+            .checked_mul(ENTRY_SIZE)
+            .ok_or(LabError::TooLarge)?;
+        let directory_size = (HEADER_SIZE as u64) + records_size as u64 + names_size as u64;
+        if directory_size > max_size {
+            return Err(LabError::TooLarge);
+        }
+
+        let mut records = vec![0u8; records_size];
+        reader.read_exact(&mut records)?;
+        let mut names = vec![0u8; names_size];
+        reader.read_exact(&mut names)?;
+
+        records
+            .chunks_exact(ENTRY_SIZE)
+            .map(|record| {
+                let name_offset = read_u32(record, 0) as usize;
+                let name = names
+                    .get(name_offset..)
+                    .and_then(|names| names.split(|&c| c == 0).next())
+                    .ok_or(LabError::InvalidEntry(name_offset))?;
+                Ok(LabEntry {
+                    name: String::from_utf8_lossy(name).to_string(),
+                    offset: read_u32(record, 4),
+                    size: read_u32(record, 8),
+                })
+            })
+            .collect()
+    }
+
+    pub fn entries(&self) -> &[LabEntry] {
+        &self.entries
+    }
+
+    /// Finds an entry by its filename, ignoring case like the game does
+    pub fn find(&self, name: &str) -> Option<&LabEntry> {
+        self.lookup
+            .get(&name.to_lowercase())
+            .map(|&i| &self.entries[i])
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.lookup.contains_key(&name.to_lowercase())
+    }
+
+    /// Opens a reader limited to a single entry's contents
+    pub fn reader(&self, entry: &LabEntry) -> Result<Take<BufReader<File>>, LabError> {
+        let mut reader = BufReader::new(File::open(&self.path)?);
+        reader.seek(SeekFrom::Start(entry.offset as u64))?;
+        Ok(reader.take(entry.size as u64))
+    }
+
+    /// Reads a whole entry into memory
+    pub fn read(&self, name: &str) -> Result<Vec<u8>, LabError> {
+        let entry = self
+            .find(name)
+            .ok_or_else(|| LabError::NotFound(name.to_string()))?;
+        let mut buffer = Vec::with_capacity(entry.size as usize);
+        self.reader(entry)?.read_to_end(&mut buffer)?;
+        Ok(buffer)
+    }
+
+    /// Streams an entry out to any writer, returning the number of bytes written
+    pub fn extract<W: Write>(&self, name: &str, dst: &mut W) -> Result<u64, LabError> {
+        let entry = self
+            .find(name)
+            .ok_or_else(|| LabError::NotFound(name.to_string()))?;
+        Ok(io::copy(&mut self.reader(entry)?, dst)?)
+    }
+}
+
+/// Every LAB archive the game loads
+pub struct Labs {
+    pub labs: Vec<Lab>,
+}
+
+impl Labs {
+    /// Opens all the LAB archives in the game directory, skipping unreadable ones
+    pub fn open() -> Labs {
+        Labs::open_in(".")
+    }
+
+    pub fn open_in<P: AsRef<Path>>(dir: P) -> Labs {
+        let options = MatchOptions {
+            case_sensitive: false,
+            ..Default::default()
+        };
+        let pattern = dir.as_ref().join("*.lab");
+        let labs = pattern
+            .to_str()
+            .and_then(|pattern| glob_with(pattern, options).ok())
+            .map(|paths| {
+                paths
+                    .filter_map(Result::ok)
+                    .filter_map(|path| Lab::open(path).ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Labs { labs }
+    }
+
+    /// Finds the archive containing a file and its entry
+    pub fn find(&self, name: &str) -> Option<(&Lab, &LabEntry)> {
+        self.labs
+            .iter()
+            .find_map(|lab| lab.find(name).map(|entry| (lab, entry)))
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.labs.iter().any(|lab| lab.contains(name))
+    }
+
+    /// Checks if a mod asset replaces a file from the game
+    ///
+    /// HQ images and animations replace the bitmap with the same name,
+    /// anything else needs to match exactly
+    pub fn is_overridden_by(&self, asset_name: &str) -> bool {
+        let path = Path::new(asset_name);
+        let extension = path.extension().and_then(|ext| ext.to_str());
+        match (path.file_stem().and_then(|stem| stem.to_str()), extension) {
+            (Some(stem), Some("png" | "mkv")) => {
+                self.contains(&format!("{}.bm", stem)) || self.contains(&format!("{}.zbm", stem))
+            }
+            _ => self.contains(asset_name),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.labs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.labs.is_empty()
+    }
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    let mut value = [0u8; 4];
+    value.copy_from_slice(&bytes[offset..offset + 4]);
+    u32::from_le_bytes(value)
+}
+
+pub enum LabError {
+    Io(io::Error),
+    InvalidHeader,
+    TooLarge,
+    InvalidEntry(usize),
+    NotFound(String),
+}
+
+impl From<io::Error> for LabError {
+    fn from(err: io::Error) -> LabError {
+        LabError::Io(err)
+    }
+}
+
+impl std::fmt::Display for LabError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LabError::Io(err) => write!(f, "Could not read LAB archive: {}", err),
+            LabError::InvalidHeader => write!(f, "Not a LAB archive"),
+            LabError::TooLarge => write!(f, "LAB directory is larger than the archive"),
+            LabError::InvalidEntry(offset) => {
+                write!(f, "LAB entry has an invalid name offset ({})", offset)
+            }
+            LabError::NotFound(name) => write!(f, "Could not find '{}' in LAB archive", name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// A LAB archive with the given files, laid out like the game's own
+    fn lab(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let names: Vec<u8> = files
+            .iter()
+            .flat_map(|(name, _)| name.bytes().chain([0]))
+            .collect();
+        let mut offset = HEADER_SIZE + files.len() * ENTRY_SIZE + names.len();
+        let mut name_offset = 0;
+
+        let mut bytes = MAGIC.to_vec();
+        for value in [0x10000, files.len(), names.len()] {
+            bytes.extend((value as u32).to_le_bytes());
+        }
+        for (name, data) in files {
+            for value in [name_offset, offset, data.len(), 0] {
+                bytes.extend((value as u32).to_le_bytes());
+            }
+            name_offset += name.len() + 1;
+            offset += data.len();
+        }
+        bytes.extend(names);
+        for (_, data) in files {
+            bytes.extend(*data);
+        }
+        bytes
+    }
+
+    fn parse(bytes: &[u8]) -> Result<Vec<LabEntry>, LabError> {
+        Lab::parse(&mut Cursor::new(bytes), bytes.len() as u64)
+    }
+
+    #[test]
+    fn valid_archive() {
+        let bytes = lab(&[("mo_tb.bm", b"bitmap"), ("Manny.cos", b"costume")]);
+        let Ok(entries) = parse(&bytes) else {
+            panic!("archive should parse");
+        };
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].name, "Manny.cos");
+        assert_eq!(entries[1].size, 7);
+        let start = entries[1].offset as usize;
+        assert_eq!(&bytes[start..start + 7], b"costume");
+    }
+
+    #[test]
+    fn reads_entries_ignoring_case() {
+        let path = std::env::temp_dir().join(format!("grimmod-test-{}.lab", std::process::id()));
+        std::fs::write(&path, lab(&[("mo_tb.bm", b"bitmap")])).unwrap();
+        let lab = Lab::open(&path);
+        let read = lab
+            .as_ref()
+            .map(|lab| (lab.contains("MO_TB.BM"), lab.read("mo_tb.bm")));
+        std::fs::remove_file(&path).ok();
+
+        let Ok((contains, Ok(data))) = read else {
+            panic!("archive should open and read");
+        };
+        assert!(contains);
+        assert_eq!(data, b"bitmap");
+        assert!(lab.is_ok_and(|lab| matches!(lab.read("missing.bm"), Err(LabError::NotFound(_)))));
+    }
+
+    #[test]
+    fn truncated_header() {
+        let bytes = lab(&[("mo_tb.bm", b"bitmap")]);
+        assert!(matches!(parse(&bytes[..10]), Err(LabError::Io(_))));
+        assert!(matches!(parse(&bytes[..20]), Err(LabError::TooLarge)));
+        assert!(matches!(parse(b"LABX"), Err(LabError::Io(_))));
+        assert!(matches!(parse(&[0; 16]), Err(LabError::InvalidHeader)));
+    }
+
+    #[test]
+    fn oversized_count() {
+        let mut bytes = lab(&[("mo_tb.bm", b"bitmap")]);
+        bytes[8..12].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(matches!(parse(&bytes), Err(LabError::TooLarge)));
+
+        let mut bytes = lab(&[("mo_tb.bm", b"bitmap")]);
+        bytes[12..16].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(matches!(parse(&bytes), Err(LabError::TooLarge)));
+    }
+
+    #[test]
+    fn invalid_name_offset() {
+        let mut bytes = lab(&[("mo_tb.bm", b"bitmap")]);
+        bytes[16..20].copy_from_slice(&100u32.to_le_bytes());
+        assert!(matches!(parse(&bytes), Err(LabError::InvalidEntry(100))));
+    }
+}
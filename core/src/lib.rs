@@ -0,0 +1,24 @@
+//! The platform-neutral side of GrimMod
+//!
+//! Everything in here can be built and tested without the game running, the
+//! Windows hook layer in the `grimmod` crate calls into it and provides the
+//! game functions through [`platform::Platform`].
+
+#![feature(if_let_guard, let_chains)]
+
+pub mod archive;
+pub mod bm;
+pub mod config;
+pub mod crash;
+pub mod debug;
+pub mod dump;
+pub mod file;
+pub mod index;
+pub mod integrity;
+pub mod lab;
+pub mod mods;
+pub mod platform;
+pub mod profile;
+pub mod renderer;
+pub mod trace;
+pub mod vfs;
@@ -0,0 +1,672 @@
+use glob::{glob, Pattern};
+use semver::{Version, VersionReq};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::read_to_string;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::archive::ModArchive;
+use crate::config::{Config, VersionPolicy};
+use crate::debug::{self, json, Level, Subsystem};
+use crate::file::ModdedFile;
+use crate::lab::Labs;
+
+pub const VERSION: Version = Version::new(1, 1, 0);
+
+const CONFLICTS_FILENAME: &str = "grimmod-conflicts.txt";
+
+/// Asset folders that are only used when selected by the config's `variants`,
+/// the game's languages and renderer modes. Any other folder counts as `common`
+pub const VARIANTS: [&str; 9] = [
+    "en",
+    "fr",
+    "de",
+    "it",
+    "es",
+    "pt",
+    "ru",
+    "original",
+    "remastered",
+];
+
+#[derive(serde::Deserialize)]
+pub struct ModInfo {
+    pub name: String,
+    pub version: Version,
+    pub author: String,
+    pub contact: String,
+    pub homepage: String,
+    pub description: String,
+    pub grimmod_version: VersionReq,
+    /// Mods with a higher priority load later and override mods with a lower one
+    #[serde(default)]
+    pub priority: i32,
+    /// Mods that must be loaded alongside this one, by name and version requirement
+    #[serde(default)]
+    pub dependencies: HashMap<String, VersionReq>,
+    /// Mods that can't be loaded alongside this one, by name and version requirement
+    #[serde(default)]
+    pub conflicts: HashMap<String, VersionReq>,
+    /// SHA-256 hex digests of the mod's assets, by their path in the `assets` folder
+    #[serde(default)]
+    pub checksums: HashMap<String, String>,
+}
+
+/// Where a mod's files are kept
+pub enum ModLocation {
+    Folder(PathBuf),
+    Archive(Arc<ModArchive>),
+}
+
+/// A mod found in the mods folder
+pub struct Mod {
+    /// The mod's folder name or archive name without its extension
+    pub name: String,
+    pub location: ModLocation,
+    pub info: Option<ModInfo>,
+    /// Why the mod's `info.json` couldn't be parsed, if it has one
+    pub info_error: Option<String>,
+}
+
+impl Mod {
+    pub fn new(name: String, location: ModLocation) -> Mod {
+        let mut modded = Mod {
+            name,
+            location,
+            info: None,
+            info_error: None,
+        };
+        if let Some(contents) = modded.read_info() {
+            match serde_json::from_str(&contents) {
+                Ok(info) => modded.info = Some(info),
+                Err(err) => modded.info_error = Some(err.to_string()),
+            }
+        }
+        modded
+    }
+
+    /// Finds every mod in the mods folder, folders first and then archives
+    pub fn discover() -> Vec<Mod> {
+        let folders = glob("./Mods/*/")
+            .map(|paths| paths.filter_map(Result::ok).collect())
+            .unwrap_or_else(|_| Vec::new());
+        let folders = folders.into_iter().filter_map(|path| {
+            let name = path.file_name()?.to_str()?.to_string();
+            Some(Mod::new(name, ModLocation::Folder(path)))
+        });
+        let archives = ModArchive::open_all().into_iter().map(|archive| {
+            let name = archive.name.clone();
+            Mod::new(name, ModLocation::Archive(Arc::new(archive)))
+        });
+
+        folders.chain(archives).collect()
+    }
+
+    /// Sorts mods into their load order
+    ///
+    /// Mods named in the config's `load_order` load last, in the order they are listed.
+    /// All other mods load before them, sorted by their `priority` and then their name.
+    pub fn load_order(mut mods: Vec<Mod>, load_order: &[String]) -> Vec<Mod> {
+        mods.sort_by_cached_key(|modded| {
+            let position = load_order.iter().position(|name| modded.is_named(name));
+            (position, modded.priority(), modded.name.to_lowercase())
+        });
+        mods
+    }
+
+    /// Checks a name against the mod's folder/archive name or its `info.json` name
+    pub fn is_named(&self, name: &str) -> bool {
+        self.name.eq_ignore_ascii_case(name)
+            || self
+                .info
+                .as_ref()
+                .is_some_and(|info| info.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Checks the config's `[mods]` table, mods not listed there are enabled
+    pub fn is_enabled(&self) -> bool {
+        let config = Config::get();
+        config.mods.enabled
+            && config
+                .mods
+                .switches
+                .iter()
+                .find(|(name, _)| self.is_named(name))
+                .map_or(true, |(_, &enabled)| enabled)
+    }
+
+    /// Checks the mod's `grimmod_version` against a policy, `Err` with the reason
+    /// if the mod mustn't be loaded
+    pub fn check_version(&self, policy: VersionPolicy) -> Result<(), String> {
+        match (&self.info, policy) {
+            (Some(info), VersionPolicy::Disable | VersionPolicy::Strict)
+                if !info.grimmod_version.matches(&VERSION) =>
+            {
+                Err(format!(
+                    "was made for grimmod {} but {} found",
+                    info.grimmod_version, VERSION
+                ))
+            }
+            (None, VersionPolicy::Strict) => Err(match &self.info_error {
+                Some(err) => format!("has a malformed info.json ({})", err),
+                None => "has no info.json".to_string(),
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn version(&self) -> Option<&Version> {
+        self.info.as_ref().map(|info| &info.version)
+    }
+
+    /// Lists every dependency or conflict of this mod that isn't satisfied by the other mods
+    pub fn unmet_requirements(&self, mods: &[Mod]) -> Vec<String> {
+        let Some(info) = &self.info else {
+            return Vec::new();
+        };
+        let find = |name: &str| {
+            mods.iter()
+                .find(|other| !std::ptr::eq(*other, self) && other.is_named(name))
+        };
+
+        let mut unmet = Vec::new();
+        for (name, req) in info.dependencies.iter() {
+            match find(name).map(Mod::version) {
+                None => unmet.push(format!("requires {} {} which isn't loaded", name, req)),
+                Some(Some(version)) if !req.matches(version) => unmet.push(format!(
+                    "requires {} {} but {} is installed",
+                    name, req, version
+                )),
+                Some(None) if *req != VersionReq::STAR => unmet.push(format!(
+                    "requires {} {} but its version is unknown",
+                    name, req
+                )),
+                Some(_) => {}
+            }
+        }
+        for (name, req) in info.conflicts.iter() {
+            match find(name).map(Mod::version) {
+                Some(Some(version)) if req.matches(version) => {
+                    unmet.push(format!("conflicts with {} {}", name, version))
+                }
+                Some(None) => unmet.push(format!("conflicts with {}", name)),
+                _ => {}
+            }
+        }
+        unmet
+    }
+
+    /// The checksum listed in `info.json` for one of the mod's files, if any
+    pub fn checksum(&self, file: &ModdedFile) -> Option<&str> {
+        let asset_path = file.asset_path()?;
+        self.info
+            .as_ref()?
+            .checksums
+            .iter()
+            .find(|(path, _)| path.replace('\\', "/").eq_ignore_ascii_case(&asset_path))
+            .map(|(_, checksum)| checksum.as_str())
+    }
+
+    pub fn priority(&self) -> i32 {
+        self.info.as_ref().map_or(0, |info| info.priority)
+    }
+
+    /// Lists every asset of the mod as its lowercase filename and file
+    ///
+    /// When the mod provides an asset in several variant folders, the one
+    /// first in the config's `variants` is picked
+    pub fn assets(&self) -> Vec<(String, ModdedFile)> {
+        let variants = Config::get().variants;
+        let mut assets: HashMap<String, (usize, ModdedFile)> = HashMap::new();
+        for (filename, variant, file) in self.variants() {
+            let Some(rank) = variant_rank(&variant, &variants) else {
+                continue;
+            };
+            match assets.get(&filename) {
+                Some((best, _)) if *best <= rank => {}
+                _ => {
+                    assets.insert(filename, (rank, file));
+                }
+            }
+        }
+
+        assets
+            .into_iter()
+            .map(|(filename, (_, file))| (filename, file))
+            .collect()
+    }
+
+    /// Lists every asset of the mod as its lowercase filename, variant folder and file
+    pub fn variants(&self) -> Vec<(String, String, ModdedFile)> {
+        match &self.location {
+            ModLocation::Folder(path) => {
+                let pattern = format!("{}/assets/*/*", Pattern::escape(&path.to_string_lossy()));
+                glob(&pattern)
+                    .map(|paths| {
+                        paths
+                            .filter_map(Result::ok)
+                            .filter(|path| path.is_file())
+                            .filter_map(|path| {
+                                let filename = path.file_name()?.to_str()?.to_lowercase();
+                                let variant = path.parent()?.file_name()?.to_str()?.to_lowercase();
+                                Some((filename, variant, ModdedFile::Loose(path)))
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default()
+            }
+            ModLocation::Archive(archive) => archive
+                .assets()
+                .filter_map(|(filename, entry)| {
+                    let variant = entry.rsplit('/').nth(1)?.to_lowercase();
+                    let modded = ModdedFile::Packed {
+                        archive: archive.clone(),
+                        entry: entry.to_string(),
+                    };
+                    Some((filename.to_string(), variant, modded))
+                })
+                .collect(),
+        }
+    }
+
+    /// Reads the mod's `info.json`, if it has one
+    pub fn read_info(&self) -> Option<String> {
+        match &self.location {
+            ModLocation::Folder(path) => read_to_string(path.join("info.json")).ok(),
+            ModLocation::Archive(archive) => archive.read_info(),
+        }
+    }
+}
+
+/// Ranks an asset folder by its position in the `variants` fallback chain, lower is better
+///
+/// Folders that aren't a known variant rank as `common` does, or last if it isn't listed.
+/// Known variants missing from the chain aren't used at all
+pub fn variant_rank(variant: &str, variants: &[String]) -> Option<usize> {
+    let position = |name: &str| {
+        variants
+            .iter()
+            .position(|variant| variant.eq_ignore_ascii_case(name))
+    };
+    if let Some(rank) = position(variant) {
+        Some(rank)
+    } else if VARIANTS.contains(&variant) {
+        None
+    } else {
+        Some(position("common").unwrap_or(variants.len()))
+    }
+}
+
+/// Splits mods into the ones whose requirements are met and the ones that can't be loaded,
+/// along with the reasons why
+///
+/// Dropping a mod can break the dependencies of others, so this repeats until nothing changes.
+/// Two mods that conflict with each other are both dropped, as there's no telling which one
+/// the player wants, until one of them is switched off in the config
+pub fn resolve(mut mods: Vec<Mod>) -> (Vec<Mod>, Vec<(Mod, Vec<String>)>) {
+    let mut rejected = Vec::new();
+    loop {
+        let unmet: Vec<_> = mods
+            .iter()
+            .map(|modded| modded.unmet_requirements(&mods))
+            .collect();
+        if unmet.iter().all(Vec::is_empty) {
+            return (mods, rejected);
+        }
+
+        let mut kept = Vec::new();
+        for (modded, unmet) in mods.into_iter().zip(unmet) {
+            if unmet.is_empty() {
+                kept.push(modded);
+            } else {
+                rejected.push((modded, unmet));
+            }
+        }
+        mods = kept;
+    }
+}
+
+/// Finds every mod in the mods folder and sorts the ones that can be loaded into
+/// their load order, logging the status of each
+pub fn load() -> Vec<Mod> {
+    Mod::load_order(validate_mods(Mod::discover()), &Config::get().load_order)
+}
+
+/// Logs the status of every mod: disabled, not loaded and why, or loaded and whether
+/// it could be validated against this version of grimmod
+///
+/// Returns the mods that can be loaded, those that haven't been switched off in the config,
+/// pass the `version_policy` and whose dependencies and conflicts are satisfied
+pub fn validate_mods(mods: Vec<Mod>) -> Vec<Mod> {
+    let policy = Config::get().version_policy;
+    let mut compatible = Vec::new();
+    for modded in mods {
+        if !modded.is_enabled() {
+            debug::event(
+                Level::Info,
+                Subsystem::General,
+                "mod_disabled",
+                json!({ "mod": modded.name }),
+                format!("Mod disabled: {}", modded.name),
+            );
+            continue;
+        }
+        match modded.check_version(policy) {
+            Ok(()) => compatible.push(modded),
+            Err(reason) => {
+                debug::event(
+                    Level::Error,
+                    Subsystem::General,
+                    "mod_rejected",
+                    json!({ "mod": modded.name, "reasons": [reason] }),
+                    format!("Mod not loaded: {} {}", modded.name, reason),
+                );
+            }
+        }
+    }
+
+    let (loaded, rejected) = resolve(compatible);
+    for (modded, unmet) in rejected {
+        debug::event(
+            Level::Error,
+            Subsystem::General,
+            "mod_rejected",
+            json!({ "mod": modded.name, "reasons": unmet }),
+            format!("Mod not loaded: {} {}", modded.name, unmet.join(", ")),
+        );
+    }
+
+    for modded in loaded.iter() {
+        match (&modded.info, &modded.info_error) {
+            (Some(info), _) if info.grimmod_version.matches(&VERSION) => {
+                debug::event(
+                    Level::Info,
+                    Subsystem::General,
+                    "mod_validated",
+                    json!({
+                        "mod": modded.name,
+                        "name": info.name,
+                        "version": info.version,
+                        "author": info.author,
+                        "homepage": info.homepage,
+                    }),
+                    format!(
+                        "Mod validated: {} {} (by {} at {})",
+                        info.name, info.version, info.author, info.homepage
+                    ),
+                );
+            }
+            (Some(info), _) => {
+                debug::event(
+                    Level::Warn,
+                    Subsystem::General,
+                    "mod_version_mismatch",
+                    json!({
+                        "mod": modded.name,
+                        "version": info.version,
+                        "grimmod_version": info.grimmod_version,
+                        "found": VERSION,
+                    }),
+                    format!(
+                        "Mod failed validation: {} {} was made for grimmod {} but {} found.",
+                        info.name, info.version, info.grimmod_version, VERSION
+                    ),
+                );
+                debug::warn("Disable it if issues arise");
+            }
+            (None, Some(err)) => {
+                debug::event(
+                    Level::Error,
+                    Subsystem::General,
+                    "mod_unvalidated",
+                    json!({ "mod": modded.name, "error": err }),
+                    format!(
+                        "Mod loaded without validation: {} has a malformed info.json ({})",
+                        modded.name, err
+                    ),
+                );
+            }
+            (None, None) => {
+                debug::event(
+                    Level::Info,
+                    Subsystem::General,
+                    "mod_unvalidated",
+                    json!({ "mod": modded.name, "error": null }),
+                    format!(
+                        "Mod loaded without validation: {} has no info.json",
+                        modded.name
+                    ),
+                );
+            }
+        }
+    }
+    loaded
+}
+
+/// Logs mod assets that don't replace any file in the game's LAB archives
+pub fn validate_overrides(mods: &[Mod], labs: &Labs) {
+    if labs.is_empty() {
+        debug::error("No LAB archives found, mod assets can't be validated");
+        return;
+    }
+    let mut unmatched = 0;
+    for modded in mods {
+        for (filename, file) in modded.assets() {
+            if !labs.is_overridden_by(&filename) {
+                debug::warn(format!(
+                    "Mod asset {} doesn't replace any game file",
+                    file.display()
+                ));
+                unmatched += 1;
+            }
+        }
+    }
+    if unmatched > 0 {
+        debug::info(format!(
+            "{unmatched} mod asset(s) don't replace any game file"
+        ));
+    }
+}
+
+/// Finds every asset provided by more than one mod
+///
+/// Each asset maps to the mods providing it in load order, so the last one wins
+pub fn find_conflicts(mods: &[Mod]) -> BTreeMap<String, Vec<&str>> {
+    let mut providers: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+    for modded in mods {
+        for (filename, _) in modded.assets() {
+            providers
+                .entry(filename)
+                .or_default()
+                .push(modded.name.as_str());
+        }
+    }
+    providers.retain(|_, mods| mods.len() > 1);
+    providers
+}
+
+/// Logs every asset provided by more than one mod and which mod wins,
+/// optionally writing the same report to `grimmod-conflicts.txt`
+pub fn report_conflicts(mods: &[Mod]) {
+    let conflicts = find_conflicts(mods);
+
+    let lines: Vec<_> = conflicts
+        .iter()
+        .filter_map(|(filename, mods)| {
+            let winner = mods.last()?;
+            Some(format!(
+                "{} is provided by {} ({} wins)",
+                filename,
+                mods.join(", "),
+                winner
+            ))
+        })
+        .collect();
+
+    if lines.is_empty() {
+        debug::info("No conflicts between mods");
+    } else {
+        debug::info(format!(
+            "{} assets are provided by more than one mod",
+            lines.len()
+        ));
+        for line in lines.iter() {
+            debug::info(format!("Conflict: {}", line));
+        }
+    }
+
+    if Config::get().logging.conflict_report {
+        let mut report = lines.join("\n");
+        report.push('\n');
+        if let Err(err) = std::fs::write(CONFLICTS_FILENAME, report) {
+            debug::error(format!("Could not write {}: {}", CONFLICTS_FILENAME, err));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn modded(name: &str, version: &str, requirements: &str) -> Mod {
+        let info = format!(
+            r#"{{"name": "{}", "version": "{}", "author": "", "contact": "", "homepage": "",
+                "description": "", "grimmod_version": "^1.0"{}}}"#,
+            name, version, requirements
+        );
+        Mod {
+            name: name.to_string(),
+            location: ModLocation::Folder(PathBuf::from(name)),
+            info: Some(serde_json::from_str(&info).unwrap()),
+            info_error: None,
+        }
+    }
+
+    fn names(mods: &[Mod]) -> Vec<&str> {
+        mods.iter().map(|modded| modded.name.as_str()).collect()
+    }
+
+    #[test]
+    fn satisfied_dependencies_are_loaded() {
+        let mods = vec![
+            modded("a", "1.0.0", r#", "dependencies": {"b": "^1.2"}"#),
+            modded("b", "1.3.0", r#", "dependencies": {"c": "*"}"#),
+            modded("c", "0.1.0", ""),
+        ];
+        assert!(mods[0].unmet_requirements(&mods).is_empty());
+
+        let (loaded, rejected) = resolve(mods);
+        assert_eq!(names(&loaded), ["a", "b", "c"]);
+        assert!(rejected.is_empty());
+    }
+
+    #[test]
+    fn missing_dependency_is_rejected() {
+        let mods = vec![modded("a", "1.0.0", r#", "dependencies": {"b": "^1"}"#)];
+        let unmet = mods[0].unmet_requirements(&mods);
+        assert_eq!(unmet, ["requires b ^1 which isn't loaded"]);
+
+        let (loaded, rejected) = resolve(mods);
+        assert!(loaded.is_empty());
+        assert_eq!(rejected.len(), 1);
+    }
+
+    #[test]
+    fn too_low_version_is_rejected() {
+        let mods = vec![
+            modded("a", "1.0.0", r#", "dependencies": {"b": ">=2.0"}"#),
+            modded("b", "1.5.0", ""),
+        ];
+        let unmet = mods[0].unmet_requirements(&mods);
+        assert_eq!(unmet, ["requires b >=2.0 but 1.5.0 is installed"]);
+
+        let (loaded, _) = resolve(mods);
+        assert_eq!(names(&loaded), ["b"]);
+    }
+
+    #[test]
+    fn broken_transitive_dependency_rejects_the_chain() {
+        let mods = vec![
+            modded("a", "1.0.0", r#", "dependencies": {"b": "*"}"#),
+            modded("b", "1.0.0", r#", "dependencies": {"c": "*"}"#),
+            modded("d", "1.0.0", ""),
+        ];
+        // a only finds out once b is dropped for missing c
+        assert!(mods[0].unmet_requirements(&mods).is_empty());
+
+        let (loaded, rejected) = resolve(mods);
+        assert_eq!(names(&loaded), ["d"]);
+        let rejected: Vec<_> = rejected
+            .iter()
+            .map(|(modded, _)| modded.name.as_str())
+            .collect();
+        assert_eq!(rejected, ["b", "a"]);
+    }
+
+    #[test]
+    fn version_policy_checks_grimmod_version() {
+        let mut future = modded("a", "1.0.0", "");
+        future.info.as_mut().unwrap().grimmod_version = VersionReq::parse("^2").unwrap();
+        assert!(future.check_version(VersionPolicy::Warn).is_ok());
+        assert!(future.check_version(VersionPolicy::Disable).is_err());
+
+        let mut unknown = modded("b", "1.0.0", "");
+        unknown.info = None;
+        assert!(unknown.check_version(VersionPolicy::Disable).is_ok());
+        assert!(unknown.check_version(VersionPolicy::Strict).is_err());
+        assert!(modded("c", "1.0.0", "")
+            .check_version(VersionPolicy::Strict)
+            .is_ok());
+    }
+
+    #[test]
+    fn mutually_conflicting_mods_are_both_rejected() {
+        let mods = vec![
+            modded("a", "1.0.0", r#", "conflicts": {"b": "*"}"#),
+            modded("b", "1.0.0", r#", "conflicts": {"a": "*"}"#),
+        ];
+        let (loaded, rejected) = resolve(mods);
+        assert!(loaded.is_empty());
+        assert_eq!(rejected.len(), 2);
+    }
+
+    #[test]
+    fn variants_rank_by_their_place_in_the_chain() {
+        let variants = ["fr", "common", "en"].map(String::from);
+        assert_eq!(variant_rank("fr", &variants), Some(0));
+        assert_eq!(variant_rank("EN", &variants), Some(2));
+        // a known variant left out of the chain isn't used
+        assert_eq!(variant_rank("de", &variants), None);
+        // any other folder ranks as common, or last without it
+        assert_eq!(variant_rank("textures", &variants), Some(1));
+        assert_eq!(variant_rank("textures", &["en".to_string()]), Some(1));
+    }
+
+    #[test]
+    fn load_order_puts_listed_mods_last() {
+        let mut high = modded("high", "1.0.0", r#", "priority": 5"#);
+        high.name = "a-high".to_string();
+        let mods = vec![
+            modded("listed-second", "1.0.0", ""),
+            high,
+            modded("b", "1.0.0", ""),
+            modded("listed-first", "1.0.0", r#", "priority": -5"#),
+            modded("a", "1.0.0", ""),
+            modded("0-late", "1.0.0", r#", "priority": 3"#),
+        ];
+        let load_order = ["Listed-First", "high", "listed-second"].map(String::from);
+
+        let sorted = Mod::load_order(mods, &load_order);
+        assert_eq!(
+            names(&sorted),
+            [
+                "a",
+                "b",
+                "0-late",
+                "listed-first",
+                "a-high",
+                "listed-second"
+            ]
+        );
+    }
+}
@@ -0,0 +1,58 @@
+use once_cell::sync::OnceCell;
+use std::ffi::{c_char, c_int, c_void};
+
+static PLATFORM: OnceCell<Box<dyn Platform>> = OnceCell::new();
+
+/// The game and graphics functions that the core needs to call into
+///
+/// The hook layer implements this with the bound game functions, anything else
+/// (e.g. tests) can swap in a mock
+pub trait Platform: Send + Sync {
+    /// Opens a file through the game's own LAB-aware file functions
+    fn open_file(&self, filename: *mut c_char, mode: *mut c_char) -> *mut c_void;
+
+    /// Closes a file opened with `open_file`
+    fn close_file(&self, file: *mut c_void) -> c_int;
+
+    /// Reads from a file opened with `open_file`
+    fn read_file(&self, file: *mut c_void, dst: *mut c_void, size: usize) -> usize;
+
+    /// Uploads the triangles carving out a video scene's static chunks
+    fn bind_cutouts(&self, triangles: &'static [f32]);
+
+    /// Every game function found so far and its address, for crash reports
+    fn bound_fns(&self) -> Vec<(&'static str, usize)>;
+}
+
+/// Sets the platform for the lifetime of the process, only the first install is kept
+pub fn install<P: Platform + 'static>(platform: P) {
+    PLATFORM.set(Box::new(platform)).ok();
+}
+
+/// Gets the installed platform or a headless one if nothing is installed
+pub fn get() -> &'static dyn Platform {
+    PLATFORM.get().map_or(&Headless, Box::as_ref)
+}
+
+/// A platform without a game attached, where nothing can be opened or drawn
+pub struct Headless;
+
+impl Platform for Headless {
+    fn open_file(&self, _filename: *mut c_char, _mode: *mut c_char) -> *mut c_void {
+        std::ptr::null_mut()
+    }
+
+    fn close_file(&self, _file: *mut c_void) -> c_int {
+        -1
+    }
+
+    fn read_file(&self, _file: *mut c_void, _dst: *mut c_void, _size: usize) -> usize {
+        0
+    }
+
+    fn bind_cutouts(&self, _triangles: &'static [f32]) {}
+
+    fn bound_fns(&self) -> Vec<(&'static str, usize)> {
+        Vec::new()
+    }
+}
@@ -0,0 +1,236 @@
+use once_cell::sync::Lazy;
+use serde_json::Value;
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, TryLockError};
+use std::time::{Duration, Instant};
+
+use crate::config::Config;
+use crate::debug::{self, json, Level, Subsystem};
+
+const DUMP_INTERVAL: Duration = Duration::from_secs(60);
+// latencies are bucketed by their top 3 bits, so percentiles are within 25%
+const BUCKETS: usize = 252;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static PROFILE: Lazy<Mutex<Profile>> = Lazy::new(|| {
+    Mutex::new(Profile {
+        stats: HashMap::new(),
+        started: Instant::now(),
+        last_dump: Instant::now(),
+    })
+});
+
+thread_local! {
+    // the time spent in timed calls made from the one currently running on this thread
+    static NESTED: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+}
+
+struct Profile {
+    stats: HashMap<&'static str, Stats>,
+    started: Instant,
+    last_dump: Instant,
+}
+
+struct Stats {
+    calls: u64,
+    total: Duration,
+    /// The total without the time spent in other timed calls made from this one
+    self_total: Duration,
+    max: Duration,
+    histogram: [u64; BUCKETS],
+}
+
+impl Stats {
+    fn new() -> Stats {
+        Stats {
+            calls: 0,
+            total: Duration::ZERO,
+            self_total: Duration::ZERO,
+            max: Duration::ZERO,
+            histogram: [0; BUCKETS],
+        }
+    }
+
+    fn record(&mut self, elapsed: Duration, self_time: Duration) {
+        self.calls += 1;
+        self.total += elapsed;
+        self.self_total += self_time;
+        self.max = self.max.max(elapsed);
+        self.histogram[bucket(elapsed.as_micros() as u64)] += 1;
+    }
+
+    /// The latency below which the given fraction of calls fall, capped at the max
+    fn percentile(&self, fraction: f64) -> Duration {
+        let target = (self.calls as f64 * fraction).ceil() as u64;
+        let mut seen = 0;
+        for (index, count) in self.histogram.iter().enumerate() {
+            seen += count;
+            if seen >= target {
+                return Duration::from_micros(bucket_floor(index)).min(self.max);
+            }
+        }
+        self.max
+    }
+}
+
+/// Times a function until dropped, doing nothing unless `logging.profile` is on
+///
+/// Timers nest, e.g. `render_scene` encloses the other hooks, so besides its total
+/// each call records its self time, which leaves out the timed calls made from it
+pub struct Timer {
+    name: &'static str,
+    start: Option<Instant>,
+    /// The nested time of the enclosing call, put back once this one ends
+    outer_nested: Duration,
+}
+
+impl Drop for Timer {
+    fn drop(&mut self) {
+        if let Some(start) = self.start {
+            let elapsed = start.elapsed();
+            let nested = NESTED.with(|nested| nested.replace(self.outer_nested + elapsed));
+            record(self.name, elapsed, elapsed.saturating_sub(nested));
+        }
+    }
+}
+
+/// Starts timing a call, usually as the first line of a hook with `let _timer = ...`
+pub fn timer(name: &'static str) -> Timer {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return Timer {
+            name,
+            start: None,
+            outer_nested: Duration::ZERO,
+        };
+    }
+    let outer_nested = NESTED.with(|nested| nested.replace(Duration::ZERO));
+    Timer {
+        name,
+        start: Some(Instant::now()),
+        outer_nested,
+    }
+}
+
+/// Picks up `logging.profile`, kept apart from the config so timers stay cheap
+pub fn configure() {
+    ENABLED.store(Config::get().logging.profile, Ordering::Relaxed);
+}
+
+fn record(name: &'static str, elapsed: Duration, self_time: Duration) {
+    let mut profile = PROFILE.lock().unwrap();
+    profile
+        .stats
+        .entry(name)
+        .or_insert_with(Stats::new)
+        .record(elapsed, self_time);
+
+    if profile.last_dump.elapsed() >= DUMP_INTERVAL {
+        profile.last_dump = Instant::now();
+        // logging takes its own locks, so the timings are logged after letting go of the profile
+        let (elapsed, lines) = (profile.started.elapsed(), profile.lines());
+        drop(profile);
+        log(elapsed, lines, true);
+    }
+}
+
+/// Logs the timings of every function called so far when the game exits
+///
+/// This runs while Windows holds the loader lock, where waiting on a lock held by
+/// another thread would hang the exit, so nothing is dumped if the timings are in
+/// use and lines are skipped if the log is
+pub fn dump() {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    let profile = match PROFILE.try_lock() {
+        Ok(profile) => profile,
+        Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner(),
+        Err(TryLockError::WouldBlock) => return,
+    };
+    let (elapsed, lines) = (profile.started.elapsed(), profile.lines());
+    drop(profile);
+    log(elapsed, lines, false);
+}
+
+impl Profile {
+    fn lines(&self) -> Vec<(String, Value)> {
+        let mut stats: Vec<_> = self.stats.iter().collect();
+        stats.sort_by_key(|(_, stats)| std::cmp::Reverse(stats.self_total));
+
+        stats
+            .into_iter()
+            .map(|(name, stats)| {
+                let (p50, p95) = (stats.percentile(0.5), stats.percentile(0.95));
+                let line = format!(
+                    "  {}: {} calls, {:?} total ({:?} self), p50 {:?}, p95 {:?}, max {:?}",
+                    name, stats.calls, stats.total, stats.self_total, p50, p95, stats.max
+                );
+                let fields = json!({
+                    "function": name,
+                    "calls": stats.calls,
+                    "total_us": stats.total.as_micros() as u64,
+                    "self_us": stats.self_total.as_micros() as u64,
+                    "p50_us": p50.as_micros() as u64,
+                    "p95_us": p95.as_micros() as u64,
+                    "max_us": stats.max.as_micros() as u64,
+                });
+                (line, fields)
+            })
+            .collect()
+    }
+}
+
+/// Logs the timings, giving up on lines rather than wait for the log if `wait` is off
+fn log(elapsed: Duration, lines: Vec<(String, Value)>, wait: bool) {
+    let event = if wait { debug::event } else { debug::try_event };
+    let header = format!("Timings since startup ({}s):", elapsed.as_secs());
+    event(Level::Info, Subsystem::General, "log", Value::Null, header);
+    for (line, fields) in lines {
+        event(Level::Info, Subsystem::General, "timing", fields, line);
+    }
+}
+
+fn bucket(micros: u64) -> usize {
+    if micros < 4 {
+        return micros as usize;
+    }
+    let exponent = 63 - micros.leading_zeros() as u64;
+    let mantissa = (micros >> (exponent - 2)) & 3;
+    ((exponent - 1) * 4 + mantissa) as usize
+}
+
+fn bucket_floor(index: usize) -> u64 {
+    if index < 4 {
+        return index as u64;
+    }
+    let exponent = index as u64 / 4 + 1;
+    let mantissa = index as u64 % 4;
+    (4 + mantissa) << (exponent - 2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nested_timers_record_self_time() {
+        ENABLED.store(true, Ordering::Relaxed);
+        {
+            let _outer = timer("test_outer");
+            std::thread::sleep(Duration::from_millis(20));
+            for _ in 0..2 {
+                let _inner = timer("test_inner");
+                std::thread::sleep(Duration::from_millis(20));
+            }
+        }
+
+        let profile = PROFILE.lock().unwrap();
+        let (outer, inner) = (&profile.stats["test_outer"], &profile.stats["test_inner"]);
+        assert_eq!((outer.calls, inner.calls), (1, 2));
+        assert_eq!(inner.self_total, inner.total);
+        assert!(outer.total >= inner.total + Duration::from_millis(20));
+        assert_eq!(outer.self_total, outer.total - inner.total);
+    }
+}
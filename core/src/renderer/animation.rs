@@ -1,6 +1,3 @@
-use std::fs::File;
-use std::io::BufReader;
-use std::path::Path;
 use std::ptr::{null, null_mut};
 use std::slice;
 use std::thread;
@@ -9,7 +6,9 @@ use webm_iterable::{
     WebmIterator,
 };
 
-use crate::debug;
+use crate::debug::{self, json, Level, Subsystem};
+use crate::file::ModdedFile;
+use crate::profile;
 use crate::renderer::image::HqImageAsyncData;
 
 struct Decoder {
@@ -50,6 +49,7 @@ impl Decoder {
     where
         F: Fn(&vpx_sys::vpx_image_t) -> Vec<u8>,
     {
+        let _timer = profile::timer("animation_decode");
         let (data, data_size) = if let DecoderMode::Color = self.mode {
             let block: Block = data.try_into().ok()?;
             let data = block.raw_frame_data();
@@ -84,9 +84,8 @@ pub enum DecoderMode {
     Alpha,
 }
 
-pub fn open<P: AsRef<Path>>(path: P, datas: Vec<HqImageAsyncData>) -> Option<(u32, u32)> {
-    let src = File::open(&path).ok()?;
-    let mut reader = BufReader::new(src);
+pub fn open(modded: ModdedFile, datas: Vec<HqImageAsyncData>) -> Option<(u32, u32)> {
+    let mut reader = modded.open().ok()?;
     let (mut width, mut height) = (None, None);
 
     for tag in WebmIterator::new(&mut reader, &[]) {
@@ -107,12 +106,20 @@ pub fn open<P: AsRef<Path>>(path: P, datas: Vec<HqImageAsyncData>) -> Option<(u3
         }
     }
 
-    let path = path.as_ref().to_owned();
     thread::spawn(move || {
         let mut datas = datas.into_iter();
-        let result = decode(&path, &mut datas);
+        let result = decode(&modded, &mut datas);
         if result.is_none() {
-            debug::error("error while decoding animation frames");
+            debug::event(
+                Level::Error,
+                Subsystem::Animation,
+                "decode_failed",
+                json!({ "path": modded.display() }),
+                format!(
+                    "error while decoding animation frames of {}",
+                    modded.display()
+                ),
+            );
             datas.for_each(|mut data| data.failed());
         }
     });
@@ -120,8 +127,8 @@ pub fn open<P: AsRef<Path>>(path: P, datas: Vec<HqImageAsyncData>) -> Option<(u3
     width.zip(height)
 }
 
-fn decode(path: &Path, datas: &mut impl Iterator<Item = HqImageAsyncData>) -> Option<()> {
-    let mut src = File::open(path).ok()?;
+fn decode(modded: &ModdedFile, datas: &mut impl Iterator<Item = HqImageAsyncData>) -> Option<()> {
+    let mut src = modded.open().ok()?;
     let mut color_decoder = Decoder::new(DecoderMode::Color)?;
     let mut alpha_decoder = Decoder::new(DecoderMode::Alpha)?;
 
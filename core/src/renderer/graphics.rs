@@ -0,0 +1,53 @@
+#[derive(Clone, Copy, Debug, Default, Hash, Eq, PartialEq)]
+pub struct ImageContainerAddr(usize);
+
+impl ImageContainerAddr {
+    pub fn from_ptr<T>(ptr: *const T) -> ImageContainerAddr {
+        ImageContainerAddr(ptr as usize)
+    }
+
+    pub fn underlying(&self) -> usize {
+        self.0
+    }
+}
+
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
+pub struct ImageAddr(usize);
+
+impl ImageAddr {
+    pub fn from_ptr<T>(ptr: *const T) -> ImageAddr {
+        ImageAddr(ptr as usize)
+    }
+
+    pub fn underlying(&self) -> usize {
+        self.0
+    }
+}
+
+/// A static or animated image container, as read from the game's memory
+pub struct ImageContainer {
+    pub name: String,
+    pub images: Vec<Image>,
+    pub original_addr: ImageContainerAddr,
+}
+
+impl ImageContainer {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn images(&self) -> Vec<&Image> {
+        self.images.iter().collect()
+    }
+
+    pub fn image_addrs(&self) -> Vec<ImageAddr> {
+        self.images.iter().map(|image| image.addr).collect()
+    }
+}
+
+/// A single image or animation frame, as read from the game's memory
+pub struct Image {
+    pub addr: ImageAddr,
+    pub width: i32,
+    pub height: i32,
+}
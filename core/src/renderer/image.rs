@@ -1,17 +1,22 @@
+use image::io::Reader as ImageReader;
 use once_cell::sync::Lazy;
 use std::collections::HashMap;
+use std::io::BufReader;
 use std::path::Path;
-use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
 use std::thread;
 
-use crate::config::Config;
-use crate::renderer::graphics::{
-    Image, ImageAddr, ImageContainer, ImageContainerAddr, SurfaceAddr, OVERLAYS,
-};
-use crate::renderer::{animation, video_cutouts};
-use crate::{debug, file};
+use crate::config::{Config, Renderer};
+use crate::debug::{self, json, Level, Subsystem};
+use crate::file::{ModdedFile, ReadSeek};
+#[cfg(feature = "video")]
+use crate::renderer::animation;
+use crate::renderer::cutouts;
+use crate::renderer::graphics::{Image, ImageAddr, ImageContainer, ImageContainerAddr};
+use crate::{file, platform, profile};
 
 pub static BACKGROUND: Mutex<Option<Background>> = Mutex::new(None);
+pub static SCENE: Lazy<Mutex<Scene>> = Lazy::new(|| Mutex::new(Scene::new(None)));
 pub static BACKGROUND_WRITES: Lazy<Mutex<BackgroundWrites>> =
     Lazy::new(|| Mutex::new(HashMap::new()));
 pub static TARGET: Mutex<Option<Target>> = Mutex::new(None);
@@ -56,9 +61,15 @@ impl HqImageContainer {
         let hq_images = HqImage::open_image(name, &images)
             .or_else(|| HqImage::open_animation(name, &images))?;
 
-        if debug::verbose() {
+        if debug::enabled(Level::Debug, Subsystem::HqImages) {
             let addrs: Vec<_> = hq_images.iter().map(HqImage::format_addr).collect();
-            debug::info(format!("Opened HQ {} ({})", name, addrs.join(", ")));
+            debug::event(
+                Level::Debug,
+                Subsystem::HqImages,
+                "hq_loaded",
+                json!({ "name": name, "images": hq_images.len() }),
+                format!("Opened HQ {} ({})", name, addrs.join(", ")),
+            );
         }
 
         Some(HqImageContainer {
@@ -95,7 +106,7 @@ impl HqImageContainer {
 
 impl HqImage {
     fn open_image(name: &str, images: &[&Image]) -> Option<Vec<HqImage>> {
-        let path = file::find_modded(&format!("{}.png", name))?;
+        let modded = file::find_modded(&format!("{}.png", name))?;
         if images.len() != 1 {
             debug::error(format!(
                 "tried to open {} as image, should be animation",
@@ -104,16 +115,23 @@ impl HqImage {
             return None;
         }
         let image = images.first()?;
-        let (width, height) = image::image_dimensions(&path).ok()?;
+        let (width, height) = open_png(&modded)?.into_dimensions().ok()?;
         let data = HqImageAsyncData::new();
 
         let mut data_clone = data.clone();
         thread::spawn(move || {
-            if let Ok(png) = image::open(path) {
+            if let Some(png) = open_png(&modded).and_then(|reader| reader.decode().ok()) {
                 let has_alpha = png.color().has_alpha();
                 let buffer = png.to_rgba8().into_vec();
                 data_clone.loaded(buffer, has_alpha);
             } else {
+                debug::event(
+                    Level::Error,
+                    Subsystem::HqImages,
+                    "decode_failed",
+                    json!({ "path": modded.display() }),
+                    format!("error while decoding image {}", modded.display()),
+                );
                 data_clone.failed();
             }
         });
@@ -129,10 +147,11 @@ impl HqImage {
         }])
     }
 
+    #[cfg(feature = "video")]
     fn open_animation(name: &str, images: &[&Image]) -> Option<Vec<HqImage>> {
-        let path = file::find_modded(&format!("{}.mkv", name))?;
+        let modded = file::find_modded(&format!("{}.mkv", name))?;
         let datas: Vec<_> = (0..images.len()).map(|_| HqImageAsyncData::new()).collect();
-        let (width, height) = animation::open(path, datas.clone())?;
+        let (width, height) = animation::open(modded, datas.clone())?;
 
         Some(
             datas
@@ -152,6 +171,11 @@ impl HqImage {
         )
     }
 
+    #[cfg(not(feature = "video"))]
+    fn open_animation(_name: &str, _images: &[&Image]) -> Option<Vec<HqImage>> {
+        None
+    }
+
     pub fn name(image_addr: ImageAddr) -> Option<String> {
         HqImage::map_loaded(image_addr, &mut HQ_IMAGES.lock().unwrap(), |hq_image| {
             Some(hq_image.name.clone())
@@ -210,7 +234,11 @@ impl HqImage {
     }
 
     fn to_background_mut(&mut self) -> Option<Background> {
-        video_cutouts::bind_for(&self.name);
+        if let Some(triangles) = cutouts::triangles_for(&self.name) {
+            let message = format!("Binding video cutouts for {}", self.name);
+            debug::log(Level::Debug, Subsystem::Cutouts, message);
+            platform::get().bind_cutouts(triangles);
+        }
         self.data.get_or_wait(|buffer, _| Background {
             name: self.name.clone(),
             width: self.width,
@@ -226,6 +254,12 @@ impl HqImage {
     }
 }
 
+/// Opens a modded image for reading its header or decoding it
+fn open_png(modded: &ModdedFile) -> Option<ImageReader<BufReader<Box<dyn ReadSeek>>>> {
+    let source = BufReader::new(modded.open().ok()?);
+    ImageReader::new(source).with_guessed_format().ok()
+}
+
 pub enum HqImageState {
     Loading,
     Failed,
@@ -270,8 +304,32 @@ impl HqImageAsyncData {
     }
 }
 
+impl Default for HqImageAsyncData {
+    fn default() -> HqImageAsyncData {
+        HqImageAsyncData::new()
+    }
+}
+
 pub type BackgroundWrites = HashMap<(u32, u32), (u32, u32)>;
 
+/// The current scene, by its HQ background name even if it opted out of it,
+/// and its renderer settings, resolved once rather than every frame
+pub struct Scene {
+    pub name: Option<String>,
+    pub renderer: Renderer,
+}
+
+impl Scene {
+    fn new(name: Option<String>) -> Scene {
+        let config = Config::get();
+        let renderer = match name.as_deref() {
+            Some(name) => config.renderer_for(name),
+            None => config.renderer,
+        };
+        Scene { name, renderer }
+    }
+}
+
 pub struct Background {
     pub name: String,
     pub width: u32,
@@ -285,9 +343,10 @@ impl Background {
     /// Write (or draw over) the HQ background
     pub fn write(image: Image, x: u32, y: u32) {
         if x == 0 && y == 0 && image.width == 640 && image.height == 480 {
-            if debug::verbose() {
+            if debug::enabled(Level::Debug, Subsystem::HqImages) {
                 let name = HqImage::name(image.addr).unwrap_or_default();
-                debug::info(format!("Setting {} as background", name));
+                let message = format!("Setting {} as background", name);
+                debug::log(Level::Debug, Subsystem::HqImages, message);
             }
             Background::set_from_image(image.addr, &mut HQ_IMAGES.lock().unwrap());
         } else {
@@ -315,8 +374,19 @@ impl Background {
     }
 
     fn set_from_image(image_addr: ImageAddr, hq_images: &mut MutexGuard<Vec<HqImageContainer>>) {
-        *BACKGROUND.lock().unwrap() =
-            HqImage::map_loaded(image_addr, hq_images, HqImage::to_background_mut);
+        let name = HqImage::map_loaded(image_addr, hq_images, |hq_image| {
+            Some(hq_image.name.clone())
+        });
+        let scene = Scene::new(name);
+        // a scene can opt out of its HQ background, leaving the original in place, which
+        // is checked before waiting for the background to load
+        let background = scene
+            .name
+            .as_ref()
+            .filter(|_| scene.renderer.hq_assets)
+            .and_then(|_| HqImage::map_loaded(image_addr, hq_images, HqImage::to_background_mut));
+        *SCENE.lock().unwrap() = scene;
+        *BACKGROUND.lock().unwrap() = background;
         *BACKGROUND_WRITES.lock().unwrap() = HashMap::new();
     }
 
@@ -332,10 +402,10 @@ impl Background {
     }
 
     pub fn is_stencilled_video_scene() -> bool {
-        if Config::get().renderer.video_cutouts
-            && let Some(background) = BACKGROUND.lock().unwrap().as_ref()
+        if let Some(background) = BACKGROUND.lock().unwrap().as_ref()
+            && SCENE.lock().unwrap().renderer.video_cutouts
         {
-            video_cutouts::triangles_for(&background.name).is_some()
+            cutouts::triangles_for(&background.name).is_some()
         } else {
             false
         }
@@ -377,6 +447,7 @@ impl Background {
     }
 
     fn overlay(&mut self, x: u32, y: u32, overlay: &mut HqImage) {
+        let _timer = profile::timer("overlay");
         if self.scale != overlay.scale {
             debug::error(format!("{} has wrong scale for background", overlay.name));
             return;
@@ -431,27 +502,26 @@ impl Background {
     }
 }
 
-pub fn get_target(surface_addr: SurfaceAddr) -> Option<Target> {
-    if surface_addr.is_bitmap_underlays() {
-        BACKGROUND
-            .lock()
-            .unwrap()
-            .is_some()
-            .then_some(Target::Background)
-    } else {
-        OVERLAYS
-            .lock()
-            .unwrap()
-            .get(&surface_addr)
-            .cloned()
-            .map(Target::Image)
-    }
+/// The renderer settings for the current scene, with its `[scenes]` overrides
+pub fn scene_renderer() -> Renderer {
+    SCENE.lock().unwrap().renderer.clone()
+}
+
+/// Resolves the current scene's renderer settings again, after the config changed
+pub fn configure() {
+    let mut scene = SCENE.lock().unwrap();
+    *scene = Scene::new(scene.name.take());
 }
 
+/// Runs `f` on the HQ image being uploaded, if it's loaded
+///
+/// Called from the upload hooks, so a lock poisoned by an earlier panic in `f` is
+/// used anyway rather than taking the game down with it
 pub fn with_target_hq_image<F: FnMut(TargetMut)>(mut f: F) {
-    let mut background = BACKGROUND.lock().unwrap();
-    let mut hq_images = HQ_IMAGES.lock().unwrap();
-    match TARGET.lock().unwrap().as_mut() {
+    let mut background = BACKGROUND.lock().unwrap_or_else(PoisonError::into_inner);
+    let mut hq_images = HQ_IMAGES.lock().unwrap_or_else(PoisonError::into_inner);
+    let mut target = TARGET.lock().unwrap_or_else(PoisonError::into_inner);
+    match target.as_mut() {
         Some(Target::Background) if let Some(background) = background.as_mut() => {
             f(TargetMut::Background(background))
         }
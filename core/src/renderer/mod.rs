@@ -0,0 +1,5 @@
+#[cfg(feature = "video")]
+pub mod animation;
+pub mod cutouts;
+pub mod graphics;
+pub mod image;
@@ -0,0 +1,92 @@
+use once_cell::sync::Lazy;
+use std::fs::File;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use crate::config::Config;
+use crate::debug;
+use crate::renderer::image::SCENE;
+
+const TRACE_FILENAME: &str = "grimmod-assets.csv";
+
+// only created once something is traced, so it's left alone with tracing off
+static TRACE_FILE: Lazy<Mutex<Option<File>>> = Lazy::new(|| {
+    let file = File::create(TRACE_FILENAME).and_then(|mut file| {
+        writeln!(file, "time,scene,file,source,mod")?;
+        Ok(file)
+    });
+    match file {
+        Ok(file) => Mutex::new(Some(file)),
+        Err(err) => {
+            debug::error(format!("Could not create {}: {}", TRACE_FILENAME, err));
+            Mutex::new(None)
+        }
+    }
+});
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Where the game got a file from
+pub enum Source<'a> {
+    /// A modded asset, from the named mod
+    Mod(&'a str),
+    /// The game's own LAB archives (or its folder)
+    Lab,
+    /// Nowhere, the game couldn't open it
+    Missing,
+}
+
+impl Source<'_> {
+    fn label(&self) -> &'static str {
+        match self {
+            Source::Mod(_) => "mod",
+            Source::Lab => "lab",
+            Source::Missing => "missing",
+        }
+    }
+}
+
+/// Picks up `logging.asset_trace`, kept apart from the config as it's checked on every open
+pub fn configure() {
+    ENABLED.store(Config::get().logging.asset_trace, Ordering::Relaxed);
+}
+
+/// Adds a file the game opened to `grimmod-assets.csv`, if `logging.asset_trace` is on
+///
+/// The scene is the name `[scenes]` matches on, that of the HQ background on screen,
+/// so it's empty in scenes without one
+pub fn record(filename: &str, source: Source) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+
+    let scene = SCENE.lock().unwrap().name.clone().unwrap_or_default();
+    let mod_name = match source {
+        Source::Mod(name) => name,
+        _ => "",
+    };
+
+    let line = [
+        debug::timestamp().as_str(),
+        &scene,
+        filename,
+        source.label(),
+        mod_name,
+    ]
+    .map(escape)
+    .join(",");
+
+    if let Some(file) = TRACE_FILE.lock().unwrap().as_mut() {
+        writeln!(file, "{}", line).ok();
+    }
+}
+
+/// Quotes a CSV field if it needs to be
+fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
@@ -0,0 +1,65 @@
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::ffi::c_void;
+use std::io::{Cursor, Read};
+use std::sync::{Arc, Mutex};
+
+use crate::file::ReadSeek;
+
+// Every virtual file is boxed so its address is unique while it's open, which
+// lets it be handed to the game as a file handle that can't clash with its own
+static FILES: Lazy<Mutex<HashMap<usize, Arc<Mutex<VirtualFile>>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// A file served to the game from any byte source instead of its LAB packs
+pub struct VirtualFile {
+    pub name: String,
+    source: Box<dyn ReadSeek>,
+}
+
+impl VirtualFile {
+    pub fn new(name: &str, source: Box<dyn ReadSeek>) -> VirtualFile {
+        VirtualFile {
+            name: name.to_string(),
+            source,
+        }
+    }
+
+    pub fn from_bytes(name: &str, bytes: Vec<u8>) -> VirtualFile {
+        VirtualFile::new(name, Box::new(Cursor::new(bytes)))
+    }
+}
+
+/// Opens a virtual file, returning the handle the game will use for it
+pub fn open(file: VirtualFile) -> *mut c_void {
+    let file = Arc::new(Mutex::new(file));
+    let handle = Arc::as_ptr(&file) as *mut c_void;
+    FILES.lock().unwrap().insert(handle as usize, file);
+
+    handle
+}
+
+/// Reads from a virtual file at its current offset, `None` if the handle isn't virtual
+pub fn read(handle: *mut c_void, dst: &mut [u8]) -> Option<usize> {
+    let file = FILES.lock().unwrap().get(&(handle as usize)).cloned()?;
+    let mut file = file.lock().unwrap();
+
+    // fill as much as possible, like fread does, rather than a single read
+    let mut total = 0;
+    while total < dst.len() {
+        match file.source.read(&mut dst[total..]) {
+            Ok(0) | Err(_) => break,
+            Ok(n) => total += n,
+        }
+    }
+    Some(total)
+}
+
+/// Closes and drops a virtual file, `false` if the handle isn't virtual
+pub fn close(handle: *mut c_void) -> bool {
+    FILES.lock().unwrap().remove(&(handle as usize)).is_some()
+}
+
+pub fn is_open(handle: *mut c_void) -> bool {
+    FILES.lock().unwrap().contains_key(&(handle as usize))
+}
@@ -1,40 +1,8 @@
-use once_cell::sync::Lazy;
 use std::ffi::CString;
-use std::fs::{File, OpenOptions};
-use std::io::Write;
 
-use crate::config::Config;
-use crate::raw::{gl, grim};
-
-const LOG_FILENAME: &str = "grimmod.log";
-
-static LOG_FILE: Lazy<Option<File>> = Lazy::new(|| {
-    OpenOptions::new()
-        .write(true)
-        .truncate(true)
-        .create(true)
-        .open(LOG_FILENAME)
-        .ok()
-});
-
-pub fn write<T: AsRef<str>>(message: T) -> Option<()> {
-    if !Config::get().logging.enabled {
-        return None;
-    }
+pub use grimmod_core::debug::{enabled, error, event, info, json, log, Level, Subsystem};
 
-    if let Some(mut log_file) = LOG_FILE.as_ref() {
-        writeln!(log_file, "{}", message.as_ref()).ok()?;
-    }
-    Some(())
-}
-
-pub fn info<T: AsRef<str>>(message: T) -> Option<()> {
-    write(format!("[INFO] {}", message.as_ref()))
-}
-
-pub fn error<T: AsRef<str>>(message: T) -> Option<()> {
-    write(format!("[ERROR] {}", message.as_ref()))
-}
+use crate::raw::{gl, grim};
 
 #[allow(dead_code)]
 pub fn gl<T: AsRef<str>>(message: T) -> Option<()> {
@@ -53,7 +21,3 @@ pub fn gl_error() -> Option<u32> {
         None
     }
 }
-
-pub fn verbose() -> bool {
-    Config::get().logging.debug
-}
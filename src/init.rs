@@ -1,19 +1,45 @@
+use grimmod_core::{
+    config::{self, Config, Verify},
+    crash, dump, file, index, integrity,
+    lab::Labs,
+    mods::{self, Mod},
+    platform, profile,
+    renderer::image,
+    trace,
+};
+
 use crate::{
-    config::Config,
-    debug, misc,
+    debug::{self, Level, Subsystem},
+    misc,
+    platform::Native,
     raw::{
         gl, grim,
-        memory::{HookError, BASE_ADDRESS},
+        memory::{BoundFn, HookError, BASE_ADDRESS},
         process, sdl,
     },
-    renderer::{graphics, video_cutouts}, file,
+    renderer::{graphics, video_cutouts},
 };
 
 pub fn main() {
-    debug::info(format!("GrimMod {} attached to GrimFandango.exe", misc::VERSION));
+    platform::install(Native);
+    crash::install();
+    profile::configure();
+    dump::configure();
+    trace::configure();
+    index::configure();
 
-    if debug::verbose() {
-        debug::info(format!("Base memory address found: 0x{:x}", *BASE_ADDRESS));
+    debug::info(format!(
+        "GrimMod {} attached to GrimFandango.exe",
+        mods::VERSION
+    ));
+    config::report();
+
+    if debug::enabled(Level::Debug, Subsystem::Hooks) {
+        debug::log(
+            Level::Debug,
+            Subsystem::Hooks,
+            format!("Base memory address found: 0x{:x}", *BASE_ADDRESS),
+        );
     }
 
     if let Err(err) = initiate_startup() {
@@ -55,7 +81,7 @@ fn post_graphics_startup() -> Result<(), String> {
         .string_err()?;
 
     video_cutouts::create_stencil_buffer();
-    misc::validate_mods();
+    config::watch();
 
     Ok(())
 }
@@ -64,17 +90,127 @@ pub fn init_hooks() -> Result<(), HookError> {
     always_on_hooks()?;
     mods_hooks()?;
     hq_assets_hooks()?;
+    dump_hooks()?;
     vsync_hooks()?;
     hdpi_fix_hooks()?;
 
     Ok(())
 }
 
+/// Applies the config if `grimmod.toml` changed, hooking or unhooking whatever it affects
+///
+/// Settings that don't need hooks, like `renderer.quick_toggle` and `logging.debug`,
+/// only need picking up again
+pub fn apply_config_reload() {
+    let Some((old, new)) = config::take_reload() else {
+        return;
+    };
+    profile::configure();
+    dump::configure();
+    trace::configure();
+    index::configure();
+    image::configure();
+
+    if let Err(err) = reload_hooks(&old, &new) {
+        debug::error(format!("Applying the reloaded config failed: {}", err));
+    }
+}
+
+fn reload_hooks(old: &Config, new: &Config) -> Result<(), String> {
+    if old.mods != new.mods
+        || old.load_order != new.load_order
+        || old.variants != new.variants
+        || old.version_policy != new.version_policy
+    {
+        // checksums are only verified at startup, `verify = "only"` would quit mid-game
+        if grim::open_file.is_hooked() {
+            index::rebuild(&mods::load());
+        } else if new.mods.enabled {
+            index::rebuild(&mods::load());
+            file_hooks().string_err()?;
+        }
+    }
+
+    if new.logging.asset_trace && !grim::open_file.is_hooked() {
+        file_hooks().string_err()?;
+    }
+
+    // only hooked alongside the rest of the hq assets hooks, dumping hooks open_bm_image too
+    if uses_video_cutouts(old) != uses_video_cutouts(new) && grim::surface_upload.is_hooked() {
+        set_hook(
+            &grim::draw_indexed_primitives,
+            graphics::draw_indexed_primitives as grim::DrawIndexedPrimitives,
+            uses_video_cutouts(new),
+        )?;
+    }
+
+    if new.dump.enabled && !old.dump.enabled {
+        dump_hooks().string_err()?;
+    }
+
+    if old.display.vsync != new.display.vsync {
+        set_hook(
+            &sdl::set_swap_interval,
+            misc::sdl_gl_set_swap_interval as sdl::SetSwapInterval,
+            new.display.vsync,
+        )?;
+        if new.display.vsync {
+            sdl::set_swap_interval(1);
+        } else {
+            sdl::set_swap_interval(misc::game_swap_interval());
+        }
+    }
+
+    // the HQ assets hooks are only installed if mods are on at startup
+    if old.mods.enabled != new.mods.enabled {
+        debug::info("Turning mods on or off requires restarting the game");
+    }
+    if old.renderer.hq_assets != new.renderer.hq_assets {
+        debug::info("Changing renderer.hq_assets requires restarting the game");
+    }
+    if old.display.hdpi_fix != new.display.hdpi_fix {
+        debug::info("Changing display.hdpi_fix requires restarting the game");
+    }
+
+    Ok(())
+}
+
+/// Video cutouts can be on for every scene or only for some of them
+fn uses_video_cutouts(config: &Config) -> bool {
+    config.renderer.video_cutouts
+        || config
+            .scenes
+            .values()
+            .any(|scene| scene.video_cutouts == Some(true))
+}
+
+/// Hooks or unhooks a function to match a setting, doing nothing if it already does
+fn set_hook<F>(bound_fn: &BoundFn<F>, replacement: F, enabled: bool) -> Result<(), String> {
+    match (enabled, bound_fn.is_hooked()) {
+        (true, false) => bound_fn.hook(replacement).string_err(),
+        (false, true) => bound_fn.unhook().string_err(),
+        _ => Ok(()),
+    }
+}
+
 /// Wraps the application entry to locate and bind now-loaded functions
 extern "stdcall" fn application_entry() {
-    match startup() {
-        Ok(_) => debug::info("Successfully initiated GrimMod feature hooks"),
-        Err(err) => debug::error(format!("GrimMod feature hooks failed to attach: {}", err)),
+    let result = crash::guard("entry", startup).unwrap_or_else(|| Err("panicked".to_string()));
+    match result {
+        Ok(_) => debug::event(
+            Level::Info,
+            Subsystem::Hooks,
+            "hooks_attached",
+            debug::json!({}),
+            "Successfully initiated GrimMod feature hooks",
+        ),
+        Err(err) => debug::event(
+            Level::Error,
+            Subsystem::Hooks,
+            "hooks_failed",
+            debug::json!({ "error": err }),
+            format!("GrimMod feature hooks failed to attach: {}", err),
+        ),
     };
 
     grim::entry();
@@ -83,7 +219,9 @@ extern "stdcall" fn application_entry() {
 /// Wraps the renderers init function to execute some code that needs
 /// to run after gfx setup is done
 pub extern "C" fn init_renderers() {
-    if let Err(err) = post_graphics_startup() {
+    let result = crash::guard("init_renderers", post_graphics_startup)
+        .unwrap_or_else(|| Err("panicked".to_string()));
+    if let Err(err) = result {
         debug::error(format!(
             "Loading auxiliary OpenGL functions failed: {}",
             err
@@ -101,12 +239,29 @@ pub fn always_on_hooks() -> Result<(), HookError> {
     Ok(())
 }
 
-/// Overload native IO functions to load modded files
+/// Checks the mods and overloads native IO functions to load modded files
+///
+/// `logging.asset_trace` needs them too, to see the files opened with mods off
 pub fn mods_hooks() -> Result<(), HookError> {
-    if !Config::get().mods {
-        return Ok(());
+    let config = Config::get();
+    // the mods folder is only searched once, with mods off none of them load
+    let mods = mods::load();
+    mods::report_conflicts(&mods);
+    mods::validate_overrides(&mods, &Labs::open());
+    if config.mods.enabled {
+        verify_mods(&mods);
+    }
+    index::rebuild(&mods);
+
+    if config.mods.enabled || config.logging.asset_trace {
+        file_hooks()?;
     }
 
+    Ok(())
+}
+
+/// Serve modded files in place of the game's own
+fn file_hooks() -> Result<(), HookError> {
     grim::open_file.hook(file::open as grim::OpenFile)?;
     grim::close_file.hook(file::close as grim::CloseFile)?;
     grim::read_file.hook(file::read as grim::ReadFile)?;
@@ -114,9 +269,29 @@ pub fn mods_hooks() -> Result<(), HookError> {
     Ok(())
 }
 
+/// Checks mod assets against their checksums before the game starts, if enabled
+fn verify_mods(mods: &[Mod]) {
+    let verify = Config::get().integrity.verify;
+    if !matches!(verify, Verify::Startup | Verify::Only) {
+        return;
+    }
+
+    let failures = integrity::verify_mods(mods);
+    if failures == 0 {
+        debug::info("All mod checksums verified");
+    } else {
+        debug::error(format!("{} mod assets failed verification", failures));
+    }
+
+    if verify == Verify::Only {
+        debug::info("Verify only mode, quitting");
+        std::process::exit(if failures == 0 { 0 } else { 1 });
+    }
+}
+
 /// Upgrade image loading and display pipeline to enable HD 32bit assets
 pub fn hq_assets_hooks() -> Result<(), HookError> {
-    if !Config::get().mods || !Config::get().renderer.hq_assets {
+    if !Config::get().mods.enabled || !Config::get().renderer.hq_assets {
         return Ok(());
     }
 
@@ -129,7 +304,7 @@ pub fn hq_assets_hooks() -> Result<(), HookError> {
     grim::setup_draw.hook(graphics::setup_draw as grim::SetupDraw)?;
     gl::delete_textures.hook(graphics::delete_textures as gl::DeleteTextures)?;
 
-    if Config::get().renderer.video_cutouts {
+    if uses_video_cutouts(&Config::get()) {
         grim::draw_indexed_primitives
             .hook(graphics::draw_indexed_primitives as grim::DrawIndexedPrimitives)?;
     }
@@ -137,6 +312,22 @@ pub fn hq_assets_hooks() -> Result<(), HookError> {
     Ok(())
 }
 
+/// Watch image loading to dump the original images, which the HQ assets hooks may already do
+pub fn dump_hooks() -> Result<(), HookError> {
+    if !Config::get().dump.enabled {
+        return Ok(());
+    }
+
+    if !grim::open_bm_image.is_hooked() {
+        grim::open_bm_image.hook(graphics::open_bm_image as grim::OpenBmImage)?;
+    }
+    if !grim::decompress_image.is_hooked() {
+        grim::decompress_image.hook(graphics::decompress_image as grim::DecompressImage)?;
+    }
+
+    Ok(())
+}
+
 /// Force VSync to be always on
 pub fn vsync_hooks() -> Result<(), HookError> {
     if !Config::get().display.vsync {
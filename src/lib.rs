@@ -1,18 +1,17 @@
-#![feature(fn_traits, if_let_guard, let_chains, tuple_trait, unboxed_closures)]
+#![feature(fn_traits, tuple_trait, unboxed_closures)]
 
-mod config;
 mod debug;
-mod file;
 mod init;
 mod macros;
 mod misc;
+mod platform;
 mod raw;
 mod renderer;
 
 use std::ffi::c_void;
 use windows::Win32::Foundation::{BOOL, HMODULE};
 use windows::Win32::System::LibraryLoader::DisableThreadLibraryCalls;
-use windows::Win32::System::SystemServices::DLL_PROCESS_ATTACH;
+use windows::Win32::System::SystemServices::{DLL_PROCESS_ATTACH, DLL_PROCESS_DETACH};
 
 #[no_mangle]
 pub extern "system" fn DllMain(
@@ -26,6 +25,9 @@ pub extern "system" fn DllMain(
             raw::glu32::bind_fns().ok();
         }
         init::main();
+    } else if fdw_reason == DLL_PROCESS_DETACH {
+        // the loader lock is held here, the dump skips anything another thread holds
+        grimmod_core::profile::dump();
     }
     BOOL(1)
 }
@@ -1,58 +1,17 @@
-use glob::glob;
-use semver::{Version, VersionReq};
 use std::ffi::{c_char, c_int, c_void};
-use std::fs::File;
-use std::io::Read;
+use std::sync::atomic::{AtomicI32, Ordering};
 use windows::Win32::Foundation::BOOL;
 use windows::Win32::Graphics::Gdi::GetMonitorInfoW;
 use windows::Win32::Graphics::Gdi::MonitorFromWindow;
 use windows::Win32::Graphics::Gdi::{MONITORINFO, MONITOR_DEFAULTTONEAREST};
 use windows::Win32::UI::WindowsAndMessaging::SetProcessDPIAware;
 
-use crate::debug;
-use crate::raw::{grim, sdl};
-
-pub const VERSION: Version = Version::new(1, 1, 0);
+use grimmod_core::profile;
 
-#[derive(serde::Deserialize)]
-pub struct ModInfo {
-    pub name: String,
-    pub version: Version,
-    pub author: String,
-    pub contact: String,
-    pub homepage: String,
-    pub description: String,
-    pub grimmod_version: VersionReq,
-}
+use crate::raw::{grim, sdl};
 
-pub fn validate_mods() {
-    let Ok(mod_infos) = glob("./Mods/*/info.json") else {
-        return;
-    };
-    for info_path in mod_infos {
-        let mut contents = String::new();
-        let info: Option<ModInfo> = info_path
-            .ok()
-            .and_then(|path| File::open(path).ok())
-            .and_then(|mut file| file.read_to_string(&mut contents).ok())
-            .and_then(|_| serde_json::from_str(&contents).ok());
-
-        if let Some(info) = info {
-            if info.grimmod_version.matches(&VERSION) {
-                debug::info(format!(
-                    "Mod validated: {} {} (by {} at {})",
-                    info.name, info.version, info.author, info.homepage
-                ));
-            } else {
-                debug::error(format!(
-                    "Mod failed validation: {} {} was made for grimmod {} but {} found.",
-                    info.name, info.version, info.grimmod_version, VERSION
-                ));
-                debug::error("Disable it if issues arise");
-            }
-        }
-    }
-}
+// what the game last asked for while vsync was forced, to go back to when it's turned off
+static GAME_SWAP_INTERVAL: AtomicI32 = AtomicI32::new(0);
 
 /// Get the game's screen's size and position
 pub fn screen_bounds() -> Option<sdl::Rect> {
@@ -87,10 +46,17 @@ pub fn screen_bounds() -> Option<sdl::Rect> {
 /// SDL function for controlling the swap interval (vsync)
 ///
 /// This is a overload for a native function that will be hooked
-pub extern "C" fn sdl_gl_set_swap_interval(_interval: c_int) -> c_int {
+pub extern "C" fn sdl_gl_set_swap_interval(interval: c_int) -> c_int {
+    let _timer = profile::timer("set_swap_interval");
+    GAME_SWAP_INTERVAL.store(interval, Ordering::Relaxed);
     sdl::set_swap_interval(1)
 }
 
+/// The swap interval the game itself wants, 0 (no vsync) until it asks for one
+pub fn game_swap_interval() -> c_int {
+    GAME_SWAP_INTERVAL.load(Ordering::Relaxed)
+}
+
 pub extern "C" fn sdl_create_window(
     title: *const c_char,
     x: c_int,
@@ -99,6 +65,7 @@ pub extern "C" fn sdl_create_window(
     h: c_int,
     flags: u32,
 ) -> *mut c_void {
+    let _timer = profile::timer("create_window");
     unsafe {
         SetProcessDPIAware();
         sdl::create_window(title, x, y, w, h, flags | sdl::WINDOW_ALLOW_HIGHDPI)
@@ -106,6 +73,7 @@ pub extern "C" fn sdl_create_window(
 }
 
 pub extern "C" fn sdl_get_display_bounds(_display_index: c_int, rect: *mut sdl::Rect) -> c_int {
+    let _timer = profile::timer("get_display_bounds");
     let Some(screen_bounds) = screen_bounds() else {
         return -1;
     };
@@ -119,6 +87,7 @@ pub extern "C" fn sdl_get_current_display_mode(
     display_index: c_int,
     mode: *mut sdl::DisplayMode,
 ) -> c_int {
+    let _timer = profile::timer("get_current_display_mode");
     unsafe {
         let result = sdl::get_current_display_mode(display_index, mode);
         if result == 0 {
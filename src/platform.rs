@@ -0,0 +1,31 @@
+use std::ffi::{c_char, c_int, c_void};
+
+use grimmod_core::platform::Platform;
+
+use crate::raw::{grim, memory};
+use crate::renderer::video_cutouts;
+
+/// The platform backed by the functions bound in the running game
+pub struct Native;
+
+impl Platform for Native {
+    fn open_file(&self, filename: *mut c_char, mode: *mut c_char) -> *mut c_void {
+        grim::open_file(filename, mode)
+    }
+
+    fn close_file(&self, file: *mut c_void) -> c_int {
+        grim::close_file(file)
+    }
+
+    fn read_file(&self, file: *mut c_void, dst: *mut c_void, size: usize) -> usize {
+        grim::read_file(file, dst, size)
+    }
+
+    fn bind_cutouts(&self, triangles: &'static [f32]) {
+        video_cutouts::bind_triangles(triangles);
+    }
+
+    fn bound_fns(&self) -> Vec<(&'static str, usize)> {
+        memory::bound_fns()
+    }
+}
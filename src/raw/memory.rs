@@ -12,10 +12,16 @@ use windows::Win32::System::Memory::{
 use windows::Win32::System::ProcessStatus::{GetModuleInformation, MODULEINFO};
 use windows::Win32::System::Threading::GetCurrentProcess;
 
-use crate::{debug, raw::process};
+use crate::{
+    debug::{self, Level, Subsystem},
+    raw::process,
+};
 
 pub static BASE_ADDRESS: Lazy<usize> = Lazy::new(|| base_address().unwrap_or(0));
 
+// every function bound so far, kept for crash reports
+static BOUND_FNS: Mutex<Vec<(&'static str, usize)>> = Mutex::new(Vec::new());
+
 pub fn base_address() -> Option<usize> {
     unsafe {
         let process_handle = GetCurrentProcess();
@@ -69,6 +75,14 @@ pub unsafe fn write<T: Sized>(address: usize, value: T) {
     });
 }
 
+/// Every function bound so far and its address
+pub fn bound_fns() -> Vec<(&'static str, usize)> {
+    match BOUND_FNS.try_lock() {
+        Ok(bound_fns) => bound_fns.clone(),
+        Err(_) => Vec::new(),
+    }
+}
+
 enum FnHook {
     Direct(Mutex<Option<RawDetour>>),
     Indirect(Mutex<Option<usize>>),
@@ -120,6 +134,7 @@ impl<F> BoundFn<F> {
         let mut addr_guard = self.addr.lock().unwrap();
         if *addr_guard == 0 {
             *addr_guard = addr;
+            BOUND_FNS.lock().unwrap().push((self.name, addr));
             Ok(())
         } else {
             Err(BindError::AlreadyBound(self.name.to_string()))
@@ -145,12 +160,16 @@ impl<F> BoundFn<F> {
         let scanner = Scanner::new(pattern);
         let result = unsafe { scanner.find(None, code_addr as _, code_size) };
         if result.is_valid() {
-            if debug::verbose() {
-                debug::info(format!(
-                    "Found address for {}: 0x{:x}",
-                    self.name,
-                    result.get_addr() as usize - offset
-                ));
+            if debug::enabled(Level::Debug, Subsystem::Hooks) {
+                debug::log(
+                    Level::Debug,
+                    Subsystem::Hooks,
+                    format!(
+                        "Found address for {}: 0x{:x}",
+                        self.name,
+                        result.get_addr() as usize - offset
+                    ),
+                );
             }
             self.bind(result.get_addr() as usize - offset)
         } else {
@@ -186,11 +205,20 @@ impl<F> BoundFn<F> {
             }
         }
 
+        if debug::enabled(Level::Trace, Subsystem::Hooks) {
+            debug::event(
+                Level::Trace,
+                Subsystem::Hooks,
+                "hook_attached",
+                debug::json!({ "function": self.name, "addr": format!("0x{:x}", addr) }),
+                format!("Hooked {} at 0x{:x}", self.name, addr),
+            );
+        }
         Ok(())
     }
 
     pub fn unhook(&self) -> Result<(), UnhookError> {
-        match &self.hook {
+        let result = match &self.hook {
             FnHook::Direct(mutex) => {
                 let raw_detour = mutex
                     .lock()
@@ -212,7 +240,22 @@ impl<F> BoundFn<F> {
                 unsafe { write(self.get_addr(), underlying_addr) };
                 Ok(())
             }
+        };
+
+        if result.is_ok() && debug::enabled(Level::Trace, Subsystem::Hooks) {
+            debug::event(
+                Level::Trace,
+                Subsystem::Hooks,
+                "hook_detached",
+                debug::json!({ "function": self.name }),
+                format!("Unhooked {}", self.name),
+            );
         }
+        result
+    }
+
+    pub fn is_hooked(&self) -> bool {
+        self.hook.is_hooked()
     }
 
     pub fn original_fn_addr(&self) -> Option<usize> {
@@ -300,6 +343,23 @@ pub enum UnhookError {
     Retour(String, retour::Error),
 }
 
+impl std::fmt::Display for UnhookError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UnhookError::NotHooked(func) => {
+                write!(f, "Tried to unhook '{}' while not hooked", func)
+            }
+            UnhookError::Retour(func, retour_err) => {
+                write!(
+                    f,
+                    "Low-level error while unhooking '{}': {:?}",
+                    func, retour_err
+                )
+            }
+        }
+    }
+}
+
 macro_rules! impl_bound_extern_fn_traits {
     ($conv:literal, $($T:ident),*) => {
         #[allow(non_snake_case)]
@@ -380,11 +440,12 @@ impl<T, F> Value<T, F> {
             None => {
                 let ref_addr = self.relative_to.get_addr() + self.offset;
                 let addr = unsafe { std::ptr::read(ref_addr as *const usize) };
-                if debug::verbose() {
-                    debug::info(format!(
-                        "Found address for static {}: 0x{:x}",
-                        self.name, addr
-                    ));
+                if debug::enabled(Level::Debug, Subsystem::Hooks) {
+                    debug::log(
+                        Level::Debug,
+                        Subsystem::Hooks,
+                        format!("Found address for static {}: 0x{:x}", self.name, addr),
+                    );
                 }
                 *addr_guard = Some(addr);
                 addr
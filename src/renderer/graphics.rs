@@ -1,45 +1,47 @@
 use once_cell::sync::Lazy;
 use std::collections::HashMap;
 use std::ffi::{c_char, c_int, c_uint, c_void, CStr};
-use std::sync::Mutex;
-
-use crate::config::Config;
-use crate::debug;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, PoisonError};
+
+use grimmod_core::config::{Config, Renderer};
+use grimmod_core::crash;
+use grimmod_core::dump;
+use grimmod_core::profile;
+use grimmod_core::renderer::graphics::{Image, ImageAddr, ImageContainer, ImageContainerAddr};
+use grimmod_core::renderer::image;
+
+use crate::debug::{self, Level, Subsystem};
+use crate::init;
 use crate::raw::{gl, grim};
-use crate::renderer::{image, video_cutouts};
+use crate::renderer::video_cutouts;
 
 pub static DECOMPRESSED: Mutex<Option<ImageAddr>> = Mutex::new(None);
 pub static OVERLAYS: Lazy<Mutex<HashMap<SurfaceAddr, ImageAddr>>> =
     Lazy::new(|| Mutex::new(HashMap::new()));
 pub static SMUSH_SURFACE: Mutex<Option<SurfaceAddr>> = Mutex::new(None);
-
-#[derive(Clone, Copy, Default, Hash, Eq, PartialEq)]
-pub struct ImageContainerAddr(usize);
-
-impl ImageContainerAddr {
-    pub fn from_ptr(ptr: *const grim::ImageContainer) -> ImageContainerAddr {
-        ImageContainerAddr(ptr as usize)
-    }
+// set when a hooked upload panicked so the surface's original pixels get uploaded instead
+static HQ_UPLOAD_FAILED: AtomicBool = AtomicBool::new(false);
+
+/// Game-specific lookups for an image address
+pub trait ImageAddrExt {
+    fn image(&self) -> Option<Image>;
+    fn original(&self) -> ImageAddr;
+    fn is_decompression_buffer(&self) -> bool;
+    fn is_clean_buffer(&self) -> bool;
+    fn is_clean_z_buffer(&self) -> bool;
+    fn is_back_buffer(&self) -> bool;
+    fn is_smush_buffer(&self) -> bool;
+    fn name(&self) -> String;
 }
 
-#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
-pub struct ImageAddr(usize);
-
-impl ImageAddr {
-    pub fn from_ptr(ptr: *const grim::Image) -> ImageAddr {
-        ImageAddr(ptr as usize)
-    }
-
-    pub fn underlying(&self) -> usize {
-        self.0
-    }
-
-    pub fn image(&self) -> Option<Image> {
-        Image::from_raw(self.0 as *const _)
+impl ImageAddrExt for ImageAddr {
+    fn image(&self) -> Option<Image> {
+        read_image(self.underlying() as *const _)
     }
 
     /// Gets the original image address before decompression
-    pub fn original(&self) -> ImageAddr {
+    fn original(&self) -> ImageAddr {
         if self.is_decompression_buffer() {
             DECOMPRESSED.lock().unwrap().unwrap_or(*self)
         } else {
@@ -47,27 +49,27 @@ impl ImageAddr {
         }
     }
 
-    pub fn is_decompression_buffer(&self) -> bool {
-        self.0 == unsafe { grim::DECOMPRESSION_BUFFER.inner_addr() }
+    fn is_decompression_buffer(&self) -> bool {
+        self.underlying() == unsafe { grim::DECOMPRESSION_BUFFER.inner_addr() }
     }
 
-    pub fn is_clean_buffer(&self) -> bool {
-        self.0 == unsafe { grim::CLEAN_BUFFER.inner_addr() }
+    fn is_clean_buffer(&self) -> bool {
+        self.underlying() == unsafe { grim::CLEAN_BUFFER.inner_addr() }
     }
 
-    pub fn is_clean_z_buffer(&self) -> bool {
-        self.0 == unsafe { grim::CLEAN_Z_BUFFER.inner_addr() }
+    fn is_clean_z_buffer(&self) -> bool {
+        self.underlying() == unsafe { grim::CLEAN_Z_BUFFER.inner_addr() }
     }
 
-    pub fn is_back_buffer(&self) -> bool {
-        self.0 == unsafe { grim::BACK_BUFFER.addr() }
+    fn is_back_buffer(&self) -> bool {
+        self.underlying() == unsafe { grim::BACK_BUFFER.addr() }
     }
 
-    pub fn is_smush_buffer(&self) -> bool {
-        self.0 == unsafe { grim::SMUSH_BUFFER.inner_addr() }
+    fn is_smush_buffer(&self) -> bool {
+        self.underlying() == unsafe { grim::SMUSH_BUFFER.inner_addr() }
     }
 
-    pub fn name(&self) -> String {
+    fn name(&self) -> String {
         if self.is_decompression_buffer() {
             format!("DECOMPRESSION_BUFFER aka {}", self.original().name())
         } else if self.is_clean_buffer() {
@@ -81,7 +83,7 @@ impl ImageAddr {
         } else if let Some(name) = image::HqImage::name(*self) {
             name
         } else {
-            format!("unknown/dynamic buffer (0x{:x})", self.0)
+            format!("unknown/dynamic buffer (0x{:x})", self.underlying())
         }
     }
 }
@@ -110,68 +112,38 @@ impl SurfaceAddr {
     }
 }
 
-pub struct ImageContainer<'a> {
-    raw: &'a grim::ImageContainer,
-    images: Vec<Image>,
-    pub original_addr: ImageContainerAddr,
-}
-
-impl<'a> ImageContainer<'a> {
-    pub fn from_raw(image_container: *const grim::ImageContainer) -> Option<ImageContainer<'a>> {
-        let original_addr = ImageContainerAddr::from_ptr(image_container);
-        unsafe { image_container.as_ref() }.map(|raw| {
-            let image_ptrs =
-                unsafe { std::slice::from_raw_parts(raw.images, raw.image_count as usize) };
-            let images = image_ptrs
-                .iter()
-                .filter_map(|&image_ptr| Image::from_raw(image_ptr))
-                .collect();
-            ImageContainer {
-                raw,
-                images,
-                original_addr,
-            }
-        })
-    }
-
-    pub fn name(&self) -> &str {
-        let cstr = unsafe { CStr::from_ptr(self.raw.name.as_ptr()) };
-        cstr.to_str().unwrap_or("")
-    }
-
-    pub fn images(&self) -> Vec<&Image> {
-        self.images.iter().collect()
-    }
-
-    pub fn image_addrs(&self) -> Vec<ImageAddr> {
-        let image_addrs = unsafe {
-            std::slice::from_raw_parts(
-                self.raw.images as *const usize,
-                self.raw.image_count as usize,
-            )
-        };
-        image_addrs.iter().map(|&addr| ImageAddr(addr)).collect()
-    }
-}
-
-pub struct Image {
-    pub addr: ImageAddr,
-    pub width: i32,
-    pub height: i32,
+/// Reads an image container and its images from the game's memory
+pub fn read_image_container(
+    image_container: *const grim::ImageContainer,
+) -> Option<ImageContainer> {
+    let original_addr = ImageContainerAddr::from_ptr(image_container);
+    unsafe { image_container.as_ref() }.map(|raw| {
+        let image_ptrs =
+            unsafe { std::slice::from_raw_parts(raw.images, raw.image_count as usize) };
+        let images = image_ptrs
+            .iter()
+            .filter_map(|&image_ptr| read_image(image_ptr))
+            .collect();
+        let name = unsafe { CStr::from_ptr(raw.name.as_ptr()) };
+        ImageContainer {
+            name: name.to_str().unwrap_or("").to_string(),
+            images,
+            original_addr,
+        }
+    })
 }
 
-impl Image {
-    pub fn from_raw(image: *const grim::Image) -> Option<Image> {
-        let addr = ImageAddr::from_ptr(image);
-        unsafe { image.as_ref() }.map(|raw| {
-            let (width, height) = (raw.attributes.width, raw.attributes.height);
-            Image {
-                addr,
-                width,
-                height,
-            }
-        })
-    }
+/// Reads an image from the game's memory
+pub fn read_image(image: *const grim::Image) -> Option<Image> {
+    let addr = ImageAddr::from_ptr(image);
+    unsafe { image.as_ref() }.map(|raw| {
+        let (width, height) = (raw.attributes.width, raw.attributes.height);
+        Image {
+            addr,
+            width,
+            height,
+        }
+    })
 }
 
 pub struct Draw {
@@ -214,45 +186,65 @@ pub extern "C" fn open_bm_image(
     param_2: u32,
     param_3: u32,
 ) -> *mut grim::ImageContainer {
+    let _timer = profile::timer("open_bm_image");
     let image_container = grim::open_bm_image(filename, param_2, param_3);
 
-    if let Some(image_container) = ImageContainer::from_raw(image_container) {
+    crash::guard("open_bm_image", || {
+        let Some(image_container) = read_image_container(image_container) else {
+            return;
+        };
+        dump::container(&image_container);
+
+        // the hook may only be there for dumping images
+        let config = Config::get();
+        if !config.mods.enabled || !config.renderer.hq_assets {
+            return;
+        }
         let removed = image::HqImageContainer::load(&image_container);
         if let Some(hq_image_container) = removed {
             unpair_overlay_surfaces(&hq_image_container);
         }
-    }
+    });
 
     image_container
 }
 
 /// Hooks resource management to drop HQ images with original image
 pub extern "C" fn manage_resource(resource: *mut grim::Resource) -> c_int {
-    let state = unsafe { (*resource).state };
-    let image_container_addr = ImageContainerAddr(unsafe { (*resource).image_container as usize });
-
-    if state == 2 {
-        let removed = image::HqImageContainer::unload(image_container_addr);
-        if let Some(hq_image_container) = removed {
-            unpair_overlay_surfaces(&hq_image_container);
+    let _timer = profile::timer("manage_resource");
+    crash::guard("manage_resource", || {
+        let state = unsafe { (*resource).state };
+        let image_container_addr =
+            ImageContainerAddr::from_ptr(unsafe { (*resource).image_container });
+
+        if state == 2 {
+            let removed = image::HqImageContainer::unload(image_container_addr);
+            if let Some(hq_image_container) = removed {
+                unpair_overlay_surfaces(&hq_image_container);
+            }
         }
-    }
+    });
 
     grim::manage_resource(resource)
 }
 
 /// Hooks decompression to track an image through the system
 pub extern "C" fn decompress_image(image: *const grim::Image) {
-    if debug::verbose() {
-        debug::info(format!(
-            "Decompressing {}",
-            ImageAddr::from_ptr(image).name()
-        ));
-    }
+    let _timer = profile::timer("decompress_image");
+    crash::guard("decompress_image", || {
+        if debug::enabled(Level::Trace, Subsystem::HqImages) {
+            debug::log(
+                Level::Trace,
+                Subsystem::HqImages,
+                format!("Decompressing {}", ImageAddr::from_ptr(image).name()),
+            );
+        }
 
-    // store the address of the last image decompressed
-    // it will shortly be copied to the clean buffer and rendered
-    *DECOMPRESSED.lock().unwrap() = Some(ImageAddr::from_ptr(image));
+        // store the address of the last image decompressed
+        // it will shortly be copied to the clean buffer and rendered
+        *DECOMPRESSED.lock().unwrap() = Some(ImageAddr::from_ptr(image));
+        dump::image(ImageAddr::from_ptr(image));
+    });
 
     grim::decompress_image(image)
 }
@@ -273,38 +265,45 @@ pub extern "C" fn copy_image(
     param_7: u32,
     param_8: u32,
 ) {
-    let src_image_addr = ImageAddr::from_ptr(src_image);
-    let dst_image_addr = ImageAddr::from_ptr(dst_image);
-
-    if debug::verbose() {
-        debug::info(format!(
-            "Copying {} to {}",
-            src_image_addr.name(),
-            dst_image_addr.name(),
-        ));
-    }
+    let _timer = profile::timer("copy_image");
+    crash::guard("copy_image", || {
+        let src_image_addr = ImageAddr::from_ptr(src_image);
+        let dst_image_addr = ImageAddr::from_ptr(dst_image);
+
+        if debug::enabled(Level::Trace, Subsystem::HqImages) {
+            debug::log(
+                Level::Trace,
+                Subsystem::HqImages,
+                format!(
+                    "Copying {} to {}",
+                    src_image_addr.name(),
+                    dst_image_addr.name(),
+                ),
+            );
+        }
 
-    let src_image_addr = ImageAddr::from_ptr(src_image).original();
+        let src_image_addr = ImageAddr::from_ptr(src_image).original();
 
-    // an image being copied to the clean buffer first means it is a background (or draws
-    // over the background) about to be rendered
-    if dst_image_addr.is_clean_buffer() {
-        if let Some(src_image) = src_image_addr.image() {
-            image::Background::write(src_image, x, y);
+        // an image being copied to the clean buffer first means it is a background (or draws
+        // over the background) about to be rendered
+        if dst_image_addr.is_clean_buffer() {
+            if let Some(src_image) = src_image_addr.image() {
+                image::Background::write(src_image, x, y);
+            }
         }
-    }
 
-    if dst_image_addr.is_back_buffer() {
-        // remove any HQ background if a fullscreen video plays as it will cover it
-        // these are not true cutscenes and don't change the scene
-        // this is unnecessary for scenes with cutout backgrounds
-        if src_image_addr.is_smush_buffer()
-            && active_smush_frame_size() == Some((640, 480))
-            && !image::Background::is_stencilled_video_scene()
-        {
-            *image::BACKGROUND.lock().unwrap() = None;
+        if dst_image_addr.is_back_buffer() {
+            // remove any HQ background if a fullscreen video plays as it will cover it
+            // these are not true cutscenes and don't change the scene
+            // this is unnecessary for scenes with cutout backgrounds
+            if src_image_addr.is_smush_buffer()
+                && active_smush_frame_size() == Some((640, 480))
+                && !image::Background::is_stencilled_video_scene()
+            {
+                *image::BACKGROUND.lock().unwrap() = None;
+            }
         }
-    }
+    });
 
     grim::copy_image(
         dst_image,
@@ -325,42 +324,61 @@ pub extern "C" fn bind_image_surface(
     param_3: u32,
     param_4: u32,
 ) -> *mut grim::Surface {
-    let image_addr = ImageAddr::from_ptr(image).original();
-    let is_hq = image::HqImage::is_loaded(image_addr);
+    let _timer = profile::timer("bind_image_surface");
+    let hq_image_addr = crash::guard("bind_image_surface", || {
+        let image_addr = ImageAddr::from_ptr(image).original();
+        (image_addr, image::HqImage::is_loaded(image_addr))
+    });
     let surface = grim::bind_image_surface(image, param_2, param_3, param_4);
     let surface_addr = SurfaceAddr::from_ptr(surface);
 
-    if image_addr.is_smush_buffer() {
-        *SMUSH_SURFACE.lock().unwrap() = Some(surface_addr);
-    }
+    let Some((image_addr, is_hq)) = hq_image_addr else {
+        return surface;
+    };
+    crash::guard("bind_image_surface", || {
+        if image_addr.is_smush_buffer() {
+            *SMUSH_SURFACE.lock().unwrap() = Some(surface_addr);
+        }
 
-    if is_hq {
-        if debug::verbose() {
-            debug::info(format!(
-                "Binding {} to surface 0x{:x}",
-                image_addr.name(),
-                surface_addr.0
-            ));
+        if is_hq {
+            if debug::enabled(Level::Trace, Subsystem::HqImages) {
+                debug::log(
+                    Level::Trace,
+                    Subsystem::HqImages,
+                    format!(
+                        "Binding {} to surface 0x{:x}",
+                        image_addr.name(),
+                        surface_addr.0
+                    ),
+                );
+            }
+            OVERLAYS.lock().unwrap().insert(surface_addr, image_addr);
+        } else {
+            OVERLAYS.lock().unwrap().remove(&surface_addr);
         }
-        OVERLAYS.lock().unwrap().insert(surface_addr, image_addr);
-    } else {
-        OVERLAYS.lock().unwrap().remove(&surface_addr);
-    }
+    });
 
     surface
 }
 
 /// Hooks texture deletion to clear out any bound HQ overlays
 pub extern "stdcall" fn delete_textures(n: gl::Sizei, textures: *const gl::Uint) {
-    let surface_addr = SurfaceAddr(textures as usize - 0x20);
-    OVERLAYS.lock().unwrap().remove(&surface_addr);
+    let _timer = profile::timer("delete_textures");
+    crash::guard("delete_textures", || {
+        let surface_addr = SurfaceAddr(textures as usize - 0x20);
+        OVERLAYS.lock().unwrap().remove(&surface_addr);
+    });
 
     gl::delete_textures(n, textures);
 }
 
 /// Hooks draw preparation to set state for HQ images
 pub extern "C" fn setup_draw(draw: *mut grim::Draw, index_buffer: *const c_void) {
-    let hq_draw = Draw::from_raw(draw).filter(|draw| draw.is_hq());
+    let _timer = profile::timer("setup_draw");
+    let hq_draw = crash::guard("setup_draw", || {
+        Draw::from_raw(draw).filter(|draw| draw.is_hq())
+    })
+    .flatten();
 
     // for hq images, use a custom shader that keeps the full resolution
     if hq_draw.is_some() {
@@ -401,13 +419,16 @@ pub extern "C" fn draw_indexed_primitives(
     param_4: u32,
     param_5: u32,
 ) {
-    if image::Background::is_stencilled_video_scene()
-        && Draw::from_raw(draw).map_or(false, |draw| draw.is_smush())
-    {
-        gl::draw_elements_base_vertex
-            .hook(draw_elements_base_vertex as gl::DrawElementsBaseVertex)
-            .ok();
-    }
+    let _timer = profile::timer("draw_indexed_primitives");
+    crash::guard("draw_indexed_primitives", || {
+        if image::Background::is_stencilled_video_scene()
+            && Draw::from_raw(draw).map_or(false, |draw| draw.is_smush())
+        {
+            gl::draw_elements_base_vertex
+                .hook(draw_elements_base_vertex as gl::DrawElementsBaseVertex)
+                .ok();
+        }
+    });
 
     grim::draw_indexed_primitives(draw, param_2, param_3, param_4, param_5)
 }
@@ -420,6 +441,7 @@ pub extern "stdcall" fn draw_elements_base_vertex(
     indicies: *mut c_void,
     basevertex: gl::Int,
 ) {
+    let _timer = profile::timer("draw_elements_base_vertex");
     video_cutouts::with_stencil(|| {
         gl::draw_elements_base_vertex(mode, count, typ, indicies, basevertex);
     });
@@ -427,26 +449,40 @@ pub extern "stdcall" fn draw_elements_base_vertex(
     gl::draw_elements_base_vertex.unhook().ok();
 }
 
+/// Finds the HQ image that should be uploaded in place of a surface's data
+pub fn get_target(surface_addr: SurfaceAddr) -> Option<image::Target> {
+    if surface_addr.is_bitmap_underlays() {
+        image::BACKGROUND
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .is_some()
+            .then_some(image::Target::Background)
+    } else {
+        OVERLAYS
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&surface_addr)
+            .cloned()
+            .map(image::Target::Image)
+    }
+}
+
 /// Hooks texture uploads swap out regular assets for their HQ versions
 pub extern "C" fn surface_upload(surface: *mut grim::Surface, image_data: *mut c_void) {
+    let _timer = profile::timer("surface_upload");
     let surface_addr = SurfaceAddr::from_ptr(surface);
-    let target = image::get_target(surface_addr);
+    let target = crash::guard("surface_upload", || get_target(surface_addr)).flatten();
 
     if target.is_none() {
-        return unsafe {
-            // call with null to reset the buffer size as it might have been changed by a hq image
-            if !image_data.is_null() && (*surface).format < 0x10 {
-                grim::surface_upload(surface, std::ptr::null_mut());
-            }
-            grim::surface_upload(surface, image_data);
-        };
+        return upload_original(surface, image_data);
     }
 
     if image_data.is_null() {
         return;
     }
 
-    *image::TARGET.lock().unwrap() = target;
+    // the upload hooks can panic while holding this, which mustn't abort the game here
+    *image::TARGET.lock().unwrap_or_else(PoisonError::into_inner) = target;
     gl::tex_image_2d
         .hook(hq_tex_image_2d as gl::TexImage2d)
         .ok();
@@ -458,20 +494,55 @@ pub extern "C" fn surface_upload(surface: *mut grim::Surface, image_data: *mut c
 
     gl::pixel_storei.unhook().ok();
     gl::tex_image_2d.unhook().ok();
-    *image::TARGET.lock().unwrap() = None;
+    *image::TARGET.lock().unwrap_or_else(PoisonError::into_inner) = None;
+
+    // the HQ image couldn't be uploaded, show the original rather than a blank texture
+    if HQ_UPLOAD_FAILED.swap(false, Ordering::SeqCst) {
+        upload_original(surface, image_data);
+    }
+}
+
+fn upload_original(surface: *mut grim::Surface, image_data: *mut c_void) {
+    unsafe {
+        // call with null to reset the buffer size as it might have been changed by a hq image
+        if !image_data.is_null() && (*surface).format < 0x10 {
+            grim::surface_upload(surface, std::ptr::null_mut());
+        }
+        grim::surface_upload(surface, image_data);
+    }
 }
 
 extern "stdcall" fn hq_tex_image_2d(
-    _target: gl::Enum,
-    _level: gl::Int,
-    _internalformat: gl::Int,
-    _width: gl::Sizei,
-    _height: gl::Sizei,
-    _border: gl::Int,
-    _format: gl::Enum,
-    _typ: gl::Enum,
-    _data: *const c_void,
+    target: gl::Enum,
+    level: gl::Int,
+    internalformat: gl::Int,
+    width: gl::Sizei,
+    height: gl::Sizei,
+    border: gl::Int,
+    format: gl::Enum,
+    typ: gl::Enum,
+    data: *const c_void,
 ) {
+    let _timer = profile::timer("tex_image_2d");
+    let uploaded = crash::guard("tex_image_2d", upload_target_hq_image);
+    if uploaded.is_none() {
+        // data is null here, so this only sizes the texture until the original is uploaded
+        gl::tex_image_2d(
+            target,
+            level,
+            internalformat,
+            width,
+            height,
+            border,
+            format,
+            typ,
+            data,
+        );
+        HQ_UPLOAD_FAILED.store(true, Ordering::SeqCst);
+    }
+}
+
+fn upload_target_hq_image() {
     fn tex_image_2d(width: u32, height: u32, ptr: *const u8) {
         gl::tex_image_2d(
             gl::TEXTURE_2D,
@@ -502,6 +573,14 @@ extern "stdcall" fn hq_tex_image_2d(
 }
 
 extern "stdcall" fn hq_pixel_storei(pname: gl::Enum, param: gl::Int) {
+    let _timer = profile::timer("pixel_storei");
+    let stored = crash::guard("pixel_storei", || set_target_row_length(pname, param));
+    if stored.is_none() {
+        gl::pixel_storei(pname, param);
+    }
+}
+
+fn set_target_row_length(pname: gl::Enum, param: gl::Int) {
     image::with_target_hq_image(|target_ref| {
         if pname == gl::UNPACK_ROW_LENGTH {
             let width = match target_ref {
@@ -517,21 +596,32 @@ extern "stdcall" fn hq_pixel_storei(pname: gl::Enum, param: gl::Int) {
 
 /// Wraps final scene draw to make the renderer toggle instant and
 /// to make the image smooth on lower res displays
+///
+/// Also applies any config changes between frames, on the game's own thread
 pub extern "C" fn render_scene(
     draw: *const grim::Draw,
     surface: *const grim::Surface,
     transition: f32,
 ) {
+    let _timer = profile::timer("render_scene");
+    let renderer = crash::guard("render_scene", || {
+        init::apply_config_reload();
+        image::scene_renderer()
+    })
+    // a panic here most likely came from a broken reload, carry on with the defaults
+    .unwrap_or_else(Renderer::new);
     unsafe {
-        let value = if transition == 1.0 && Config::get().renderer.quick_toggle {
+        let value = if transition == 1.0 && renderer.quick_toggle {
             1.0
         } else {
             grim::RENDERING_MODE.get()
         };
 
-        gl::sampler_parameteri
-            .hook(forced_linear_sampler_parameteri)
-            .ok();
+        if renderer.linear_filtering {
+            gl::sampler_parameteri
+                .hook(forced_linear_sampler_parameteri)
+                .ok();
+        }
         grim::render_scene(draw, surface, value);
         gl::sampler_parameteri.unhook().ok();
     }
@@ -542,6 +632,7 @@ pub extern "stdcall" fn forced_linear_sampler_parameteri(
     pname: gl::Enum,
     param: gl::Int,
 ) {
+    let _timer = profile::timer("sampler_parameteri");
     if pname == gl::TEXTURE_MIN_FILTER || pname == gl::TEXTURE_MAG_FILTER {
         gl::sampler_parameteri(target, pname, gl::LINEAR as gl::Int);
     } else {
@@ -559,6 +650,7 @@ pub extern "stdcall" fn compressed_tex_image2d(
     image_size: gl::Sizei,
     data: *const c_void,
 ) {
+    let _timer = profile::timer("compressed_tex_image2d_arb");
     gl::compressed_tex_image2d(
         target,
         level,
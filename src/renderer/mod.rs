@@ -1,4 +1,2 @@
-pub mod animation;
 pub mod graphics;
-pub mod image;
 pub mod video_cutouts;